@@ -0,0 +1,44 @@
+-- Tables from before migrations were kept are left as they are.
+CREATE SCHEMA IF NOT EXISTS ennui;
+
+CREATE TABLE IF NOT EXISTS ennui.help (
+    helpid serial,
+    title text,
+    hook text[],
+    description text
+);
+
+CREATE TABLE IF NOT EXISTS ennui.item (
+    itemid serial,
+    name text,
+    display text,
+    description text,
+    hook text[],
+    attributes int4[]
+);
+
+CREATE TABLE IF NOT EXISTS ennui.recipe (
+    recid serial,
+    itemid int,
+    crafting_req int,
+    exploration_req int,
+    combat_req int
+);
+
+ALTER TABLE ennui.recipe ADD COLUMN IF NOT EXISTS template int;
+
+CREATE TABLE IF NOT EXISTS ennui.template (
+    id bigint PRIMARY KEY,
+    item text NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ennui.account (
+    name text PRIMARY KEY,
+    role text NOT NULL,
+    password text NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ennui.save (
+    name text PRIMARY KEY,
+    data bytea NOT NULL
+);
@@ -0,0 +1,52 @@
+CREATE TABLE IF NOT EXISTS help (
+    helpid INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS help_hook (
+    helpid INTEGER NOT NULL REFERENCES help (helpid),
+    hook TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS item (
+    itemid INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    display TEXT NOT NULL,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS item_hook (
+    itemid INTEGER NOT NULL REFERENCES item (itemid),
+    hook TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS item_attribute (
+    itemid INTEGER NOT NULL REFERENCES item (itemid),
+    attribute INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS recipe (
+    recid INTEGER PRIMARY KEY,
+    itemid INTEGER REFERENCES item (itemid),
+    crafting_req INTEGER NOT NULL,
+    exploration_req INTEGER NOT NULL,
+    combat_req INTEGER NOT NULL,
+    template INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS template (
+    id INTEGER PRIMARY KEY,
+    item TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS account (
+    name TEXT PRIMARY KEY,
+    role TEXT NOT NULL,
+    password TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS save (
+    name TEXT PRIMARY KEY,
+    data BLOB NOT NULL
+);
@@ -1,30 +1,6 @@
+-- Fills in the help files, items and recipes. The tables are made by `ennui migrate up`.
 BEGIN;
 
-CREATE SCHEMA IF NOT EXISTS ennui;
-CREATE TABLE IF NOT EXISTS ennui.help (
-    helpid serial,
-    title text,
-    hook text[],
-    description text
-);
-
-CREATE TABLE IF NOT EXISTS ennui.item (
-    itemid serial,
-    name text,
-    display text,
-    description text,
-    hook text[],
-    attributes int4[]
-);
-
-CREATE TABLE IF NOT EXISTS ennui.recipe (
-    recid serial,
-    itemid int,
-    crafting_req int,
-    exploration_req int,
-    combat_req int
-);
-
 CREATE TEMPORARY TABLE thing (
     title text,
     hook text[],
@@ -43,7 +19,8 @@ CREATE TEMPORARY TABLE tmprecipe (
     itemname text,
     crafting_req int,
     exploration_req int,
-    combat_req int
+    combat_req int,
+    template int
 );
 
 \copy thing FROM './populate.csv' WITH (FORMAT csv);
@@ -93,7 +70,8 @@ UPDATE ennui.recipe
 SET combat_req = t.combat_req,
     exploration_req = t.exploration_req,
     crafting_req = t.crafting_req,
-    itemid = (SELECT ei.itemid FROM ennui.item ei WHERE ei.name = t.itemname)
+    itemid = (SELECT ei.itemid FROM ennui.item ei WHERE ei.name = t.itemname),
+    template = t.template
 FROM tmprecipe t
 WHERE 
     ennui.recipe.combat_req = t.combat_req and
@@ -101,9 +79,9 @@ WHERE
     ennui.recipe.exploration_req = t.exploration_req;
 
 INSERT INTO ennui.recipe
-(itemid, combat_req, exploration_req, crafting_req)
+(itemid, combat_req, exploration_req, crafting_req, template)
 SELECT (SELECT ei.itemid FROM ennui.item ei WHERE ei.name = t.itemname),
-    t.combat_req, t.exploration_req, t.crafting_req
+    t.combat_req, t.exploration_req, t.crafting_req, t.template
 FROM tmprecipe t
 LEFT OUTER JOIN ennui.recipe r
     ON 
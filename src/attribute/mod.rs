@@ -32,6 +32,7 @@ pub enum Quality {
     Container = 6,
     Guard = 7,
     Key = 8,
+    Stackable = 9,
 }
 
 impl TryFrom<i32> for Quality {
@@ -48,6 +49,7 @@ impl TryFrom<i32> for Quality {
             q if q == Quality::Container as i32 => Ok(Quality::Container),
             q if q == Quality::Guard as i32 => Ok(Quality::Guard),
             q if q == Quality::Key as i32 => Ok(Quality::Key),
+            q if q == Quality::Stackable as i32 => Ok(Quality::Stackable),
             _ => Err("could not make conversion".into()),
         }
     }
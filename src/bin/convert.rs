@@ -1,5 +1,10 @@
+use ennui::item::template::ItemTemplate;
 use ennui::map::Room;
 use ennui::player::npc::YamlPlayer;
+use ennui::text::chat::ChatChannel;
+use ennui::text::social::Social;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::env::args;
 use std::fs;
 use std::io;
@@ -8,63 +13,44 @@ fn main() -> io::Result<()> {
     if let [kind, srcfile, dstfile, ..] = args().skip(1).take(3).collect::<Vec<String>>().as_slice()
     {
         match kind.as_str() {
-            "npc" => {
-                let x = fs::read(srcfile)?;
-                let r: Vec<YamlPlayer> = match serde_yaml::from_slice(&x[..]) {
-                    Ok(y) => y,
-                    Err(err) => {
-                        eprintln!("{}", err);
-                        eprintln!("in file {} on line number {}", file!(), line!());
-
-                        std::process::exit(1)
-                    }
-                };
-                // eprintln!("{:#?}", r);
-                eprintln!("in file {} on line number {}", file!(), line!());
+            "npc" => convert::<Vec<YamlPlayer>>(srcfile, dstfile)?,
+            "socials" => convert::<Vec<Social>>(srcfile, dstfile)?,
+            "channels" => convert::<Vec<ChatChannel>>(srcfile, dstfile)?,
+            "items" => convert::<Vec<ItemTemplate>>(srcfile, dstfile)?,
+            _ => convert::<Vec<Room>>(srcfile, dstfile)?,
+        }
+    } else {
+        eprintln!("usage: convert [map/npc/items/socials/channels] <srcfile> <dstfile>");
+        eprintln!("in file {} on line number {}", file!(), line!());
+    }
 
-                let buf = match serde_cbor::to_vec(&r) {
-                    Ok(y) => y,
-                    Err(err) => {
-                        eprintln!("{}", err);
-                        eprintln!("in file {} on line number {}", file!(), line!());
+    Ok(())
+}
 
-                        std::process::exit(1)
-                    }
-                };
-                std::fs::File::create(dstfile)?;
-                std::fs::write(dstfile, buf)?;
-            }
-            _ => {
-                let x = fs::read(srcfile)?;
-                let r: Vec<Room> = match serde_yaml::from_slice(&x[..]) {
-                    Ok(y) => y,
-                    Err(err) => {
-                        eprintln!("{}", err);
-                        eprintln!("in file {} on line number {}", file!(), line!());
+fn convert<T: DeserializeOwned + Serialize>(srcfile: &str, dstfile: &str) -> io::Result<()> {
+    let x = fs::read(srcfile)?;
+    let r: T = match serde_yaml::from_slice(&x[..]) {
+        Ok(y) => y,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("in file {} on line number {}", file!(), line!());
 
-                        std::process::exit(1)
-                    }
-                };
-                // eprintln!("{:#?}", r);
-                eprintln!("in file {} on line number {}", file!(), line!());
+            std::process::exit(1)
+        }
+    };
+    eprintln!("in file {} on line number {}", file!(), line!());
 
-                let buf = match serde_cbor::to_vec(&r) {
-                    Ok(y) => y,
-                    Err(err) => {
-                        eprintln!("{}", err);
-                        eprintln!("in file {} on line number {}", file!(), line!());
+    let buf = match serde_cbor::to_vec(&r) {
+        Ok(y) => y,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("in file {} on line number {}", file!(), line!());
 
-                        std::process::exit(1)
-                    }
-                };
-                std::fs::File::create(dstfile)?;
-                std::fs::write(dstfile, buf)?;
-            }
+            std::process::exit(1)
         }
-    } else {
-        eprintln!("usage: convert [map/npc] <srcfile> <dstfile>");
-        eprintln!("in file {} on line number {}", file!(), line!());
-    }
+    };
+    std::fs::File::create(dstfile)?;
+    std::fs::write(dstfile, buf)?;
 
     Ok(())
 }
@@ -3,11 +3,11 @@ use std::net::TcpListener;
 use std::sync::{Arc, Mutex};
 use std::thread::{spawn, JoinHandle};
 
+use ennui::db::{self, DBError};
 use ennui::error::EnnuiError;
 
-use ennui::game::{Game, GameResult, NpcInit};
-use ennui::player::npc::YamlPlayer;
-use ennui::player::{Player, PlayerType, Uuid};
+use ennui::game::{load_npcs, Game, GameResult, NpcInit, Snapshot, TICK};
+use ennui::player::{Player, PlayerType, Role, Uuid};
 use ennui::text::message::{Broadcast, FightAudience, MessageFormat};
 
 use ennui::fight::FightMessage;
@@ -47,10 +47,38 @@ where
     }
 }
 
+/// Checks that the database is at the schema version this build expects. A database that can't
+/// be reached, or that has never been migrated, is only complained about, since the game runs
+/// without it, but one that's behind, or that was migrated by a newer build, stops the server.
+fn check_db() -> GameResult<()> {
+    match db::connect() {
+        Ok(_) => Ok(()),
+        Err(e)
+            if matches!(
+                e.downcast_ref(),
+                Some(DBError::UnknownVersion(_)) | Some(DBError::NeedsMigrating(Some(_)))
+            ) =>
+        {
+            Err(e)
+        }
+        Err(e) => {
+            eprintln!("unable to open the database: {}", e);
+            Ok(())
+        }
+    }
+}
+
 fn main() -> GameResult<()> {
+    check_db()?;
     let listener = TcpListener::bind("0.0.0.0:8089")?;
 
-    let g = Game::new()?;
+    let mut g = Game::new()?;
+    if std::env::var("ENNUI_HUNGER").is_ok() {
+        g.enable_hunger();
+    }
+    if let Ok(ticks) = std::env::var("ENNUI_CORPSE_DECAY") {
+        g.set_corpse_decay(ticks.parse()?);
+    }
     let shared_game = arc_mutex!(g);
 
     let (sender, receiver) = channel::<JoinHandle<std::io::Result<()>>>();
@@ -74,15 +102,31 @@ fn main() -> GameResult<()> {
     let rcv = GameActor(discrete_receiver);
     rcv.start(shared_game.clone());
 
+    let ticker = discrete_sender.clone();
+    spawn(move || loop {
+        std::thread::sleep(TICK);
+        if ticker.send(DiscreteMessage::Tick).is_err() {
+            break;
+        }
+    });
+
     shared_game.lock().unwrap().set_fight_sender(fight_sender);
     shared_game
         .lock()
         .unwrap()
         .set_discrete_sender(discrete_sender);
 
-    let npcs = load_npcs()?;
+    let npcs = load_npcs(shared_game.lock().unwrap().templates())?;
     shared_game.init_npcs(npcs)?;
 
+    if let Ok(path) = std::env::var("ENNUI_SNAPSHOT") {
+        let mut g = shared_game.lock().unwrap();
+        if std::path::Path::new(&path).exists() {
+            g.restore(Snapshot::load(&path)?)?;
+        }
+        g.set_snapshot_path(path);
+    }
+
     for stream in listener.incoming() {
         let game_clone = shared_game.clone();
 
@@ -169,6 +213,10 @@ fn handle_client(p: u128, g: Arc<Mutex<Game>>) -> std::io::Result<()> {
                         g.remove_player(p);
                         break;
                     }
+                    EnnuiError::Shutdown => {
+                        g.shutdown();
+                        std::process::exit(0);
+                    }
                     EnnuiError::Fatal(s) => {
                         eprintln!("[{}]: {}", "FATAL".color(Red), s);
                         eprintln!("in file {} on line number {}", file!(), line!());
@@ -224,29 +272,54 @@ fn get_and_set_player_name(p: u128, g: Arc<Mutex<Game>>) -> std::io::Result<()>
         .map_err(|_| std::io::ErrorKind::AddrNotAvailable)?
         .map_err(|_| std::io::ErrorKind::AddrNotAvailable)?;
 
-    stream.write_all(b"enter your name: ")?;
-    let name = stream.read_line()?;
+    let pool = g.lock().unwrap().db();
+    let (name, role) = loop {
+        stream.write_all(b"enter your name: ")?;
+        let name = stream.read_line()?;
+
+        // names with an account behind them need its password, and only then get its role
+        let account = match pool.run(|db| db.account(&name)) {
+            Ok(account) => account,
+            Err(e) => {
+                eprintln!("[{}]: unable to look up account: {}", "ERROR".color(Red), e);
+                eprintln!("in file {} on line number {}", file!(), line!());
+
+                None
+            }
+        };
+
+        match account {
+            Some(account) => {
+                stream.write_all(b"password: ")?;
+                let password = stream.read_line()?;
+                if account.check(password.trim_end_matches('\r')) {
+                    break (name, account.role);
+                }
+
+                eprintln!("[{}]: wrong password for {}", "ERROR".color(Red), name);
+                stream.write_all(b"wrong password\n\n")?;
+            }
+            None => break (name, Role::Player),
+        }
+    };
     stream.write_all(b" > ")?;
 
     let mut g = g.lock().unwrap();
     let res = g
         .set_player_name(p, &name)
+        .and_then(|_| g.set_player_role(p, role))
         .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound));
+    if role > Role::Player {
+        eprintln!(
+            "[{}]: {} logged in as {:?}",
+            "SUCCESS".color(Green),
+            name,
+            role
+        );
+    }
 
     g.announce_player(p)
         .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
 
     res
 }
-
-fn load_npcs() -> GameResult<Vec<PlayerType>> {
-    let bytes = include_bytes!("../../data/npc.cbor");
-    let v: Vec<YamlPlayer> = serde_cbor::from_slice(bytes)?;
-    let mut ret: Vec<PlayerType> = vec![];
-
-    for yp in v.into_iter() {
-        ret.push(yp.into());
-    }
-
-    Ok(ret)
-}
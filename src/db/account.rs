@@ -0,0 +1,69 @@
+use crate::player::Role;
+use pbkdf2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use pbkdf2::{Params, Pbkdf2};
+use rand::Rng;
+
+/// How many rounds of PBKDF2 new passwords are put through. The rounds are kept with each hash,
+/// so raising this leaves older passwords working.
+const ROUNDS: u32 = 10_000;
+
+/// `Account` is a name that can only be played under with the right password, and the role it
+/// gives whoever logs in with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub role: Role,
+    /// The salted hash of the password as a PHC string, as written by `Account::new`. Accounts
+    /// whose hash can't be read can't be logged in to.
+    pub password: String,
+}
+
+impl Account {
+    pub fn new(role: Role, password: &str) -> Self {
+        let salt: [u8; 16] = rand::thread_rng().gen();
+        let salt = SaltString::b64_encode(&salt).expect("16 bytes make a valid salt");
+        let params = Params {
+            rounds: ROUNDS,
+            ..Params::default()
+        };
+        let hash = Pbkdf2
+            .hash_password(password.as_bytes(), None, None, params, salt.as_salt())
+            .expect("PBKDF2 takes any password");
+
+        Self {
+            role,
+            password: hash.to_string(),
+        }
+    }
+
+    /// Whether the password is the one the account was made with
+    pub fn check(&self, password: &str) -> bool {
+        match PasswordHash::new(&self.password) {
+            Ok(hash) => Pbkdf2.verify_password(password.as_bytes(), &hash).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod account_test {
+    use super::*;
+
+    #[test]
+    fn passwords() {
+        let account = Account::new(Role::Admin, "hunter2");
+        assert!(account.password.starts_with("$pbkdf2-sha256$"));
+        assert!(account.check("hunter2"));
+        assert!(!account.check("hunter3"));
+        assert!(!account.check(""));
+        assert_ne!(
+            account.password,
+            Account::new(Role::Admin, "hunter2").password
+        );
+
+        let locked = Account {
+            role: Role::Admin,
+            password: String::new(),
+        };
+        assert!(!locked.check(""));
+    }
+}
@@ -0,0 +1,155 @@
+use super::{DBError, DBResult, Storage};
+
+/// `Migration` is one numbered step in the layout of the database. Each backend has its own,
+/// written out in `migrations/<backend>/` and built into the binary.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub const POSTGRES: &[Migration] = &[Migration {
+    version: 1,
+    name: "initial",
+    sql: include_str!("../../migrations/postgres/0001_initial.sql"),
+}];
+
+pub const SQLITE: &[Migration] = &[Migration {
+    version: 1,
+    name: "initial",
+    sql: include_str!("../../migrations/sqlite/0001_initial.sql"),
+}];
+
+/// `Status` is how far along a database is, next to the migrations this build knows about
+#[derive(Debug)]
+pub struct Status {
+    /// Every version that has been run, oldest first
+    pub applied: Vec<u32>,
+    /// The migrations that have yet to be run, in the order they will be
+    pub pending: Vec<&'static Migration>,
+    /// Versions that have been run but that this build has never heard of, which means the
+    /// database was migrated by something newer
+    pub unknown: Vec<u32>,
+}
+
+impl Status {
+    /// The schema version of the database, or `None` if it has never been migrated
+    pub fn version(&self) -> Option<u32> {
+        self.applied.iter().copied().max()
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self.version() {
+            Some(v) => writeln!(fmt, "schema version {}", v)?,
+            None => writeln!(fmt, "no migrations have been run")?,
+        }
+        for m in &self.pending {
+            writeln!(fmt, "pending: {:04} {}", m.version, m.name)?;
+        }
+        for v in &self.unknown {
+            writeln!(fmt, "unknown: {:04}", v)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn status(db: &mut dyn Storage) -> DBResult<Status> {
+    let mut applied = db.applied()?;
+    applied.sort_unstable();
+
+    let known = db.migrations();
+    let pending = known
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect();
+    let unknown = applied
+        .iter()
+        .copied()
+        .filter(|v| !known.iter().any(|m| m.version == *v))
+        .collect();
+
+    Ok(Status {
+        applied,
+        pending,
+        unknown,
+    })
+}
+
+/// Runs every migration that hasn't been, oldest first, and gives back the versions it ran. A
+/// database that has been migrated past what this build knows is left alone, and is an error.
+pub fn up(db: &mut dyn Storage) -> DBResult<Vec<u32>> {
+    let status = status(db)?;
+    if !status.unknown.is_empty() {
+        return Err(DBError::UnknownVersion(status.version().unwrap_or_default()).into());
+    }
+
+    let mut ran = vec![];
+    for m in status.pending {
+        db.apply(m)
+            .map_err(|e| format!("migration {:04} {} failed: {}", m.version, m.name, e))?;
+        ran.push(m.version);
+    }
+    Ok(ran)
+}
+
+/// Checks that the database is at the schema version this build expects, without changing it
+pub fn check(db: &mut dyn Storage) -> DBResult<()> {
+    let status = status(db)?;
+    if !status.unknown.is_empty() {
+        return Err(DBError::UnknownVersion(status.version().unwrap_or_default()).into());
+    }
+    if !status.pending.is_empty() {
+        return Err(DBError::NeedsMigrating(status.version()).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod migrate_test {
+    use super::*;
+    use crate::db::Sqlite;
+
+    #[test]
+    fn migrations_are_numbered_in_order() {
+        for migrations in &[POSTGRES, SQLITE] {
+            for (i, m) in migrations.iter().enumerate() {
+                assert_eq!(m.version as usize, i + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn sqlite_migrate() {
+        let mut db = Sqlite::memory().unwrap();
+        assert_eq!(status(&mut db).unwrap().version(), None);
+        assert!(matches!(
+            check(&mut db).unwrap_err().downcast_ref::<DBError>(),
+            Some(DBError::NeedsMigrating(None))
+        ));
+        assert_eq!(up(&mut db).unwrap().len(), SQLITE.len());
+
+        let current = status(&mut db).unwrap();
+        assert_eq!(current.version(), Some(SQLITE.len() as u32));
+        assert!(current.pending.is_empty());
+        assert!(up(&mut db).unwrap().is_empty());
+        check(&mut db).unwrap();
+
+        db.apply(&Migration {
+            version: 999,
+            name: "from the future",
+            sql: "CREATE TABLE future (id INTEGER);",
+        })
+        .unwrap();
+        let newer = status(&mut db).unwrap();
+        assert_eq!(newer.version(), Some(999));
+        assert_eq!(newer.unknown, vec![999]);
+        let err = up(&mut db).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DBError>(),
+            Some(DBError::UnknownVersion(999))
+        ));
+    }
+}
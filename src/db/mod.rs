@@ -1,157 +1,238 @@
 use crate::attribute::Quality;
 use crate::describe::Description;
 use crate::hook::Hook;
+use crate::item::template::{ItemTemplate, Templates};
 use crate::item::{DescriptionWithQualities, Item};
-use postgres::{Client, NoTls};
+use crate::player::Role;
+use crate::soul::recipe::Recipe;
+use migrate::Migration;
 use std::convert::TryInto;
+use std::time::Duration;
+
+mod account;
+pub mod migrate;
+pub mod pool;
+pub mod postgres;
 mod sql;
+pub mod sqlite;
+
+pub use self::account::Account;
+pub use self::pool::Pool;
+pub use self::postgres::Postgres;
+pub use self::sqlite::Sqlite;
+
+/// Where the game keeps its database unless `ENNUI_DB` says otherwise
+pub const DEFAULT_DB: &str = "ennui.db";
+
+/// How long to wait on the database, for connecting, for a query or for a locked table, before
+/// giving up
+pub const DB_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type DBResult<T> = Result<T, Box<dyn std::error::Error>>;
 
 #[derive(Debug, Clone)]
 pub enum DBError {
     NoRows,
+    /// The database has been migrated to a schema version this build doesn't know about
+    UnknownVersion(u32),
+    /// The database has migrations still to run, and is at the given schema version, if any
+    NeedsMigrating(Option<u32>),
 }
 
 impl std::fmt::Display for DBError {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(fmt, "{:?}", self)
+        match self {
+            DBError::UnknownVersion(v) => write!(
+                fmt,
+                "the database has schema version {}, which this build doesn't know about",
+                v
+            ),
+            DBError::NeedsMigrating(v) => write!(
+                fmt,
+                "the database is at schema version {}, and needs `ennui migrate up` to be run",
+                v.map_or("none".to_owned(), |v| v.to_string())
+            ),
+            _ => write!(fmt, "{:?}", self),
+        }
     }
 }
 
 impl std::error::Error for DBError {}
 
-pub struct DB {
-    conn: Client,
-}
+/// `Storage` is everything the game keeps in a database: help files, recipes and the items they
+/// make, item templates, accounts and saved state. Each backend lays out its own tables.
+pub trait Storage: Send {
+    /// The help file with the given keyword, title first
+    fn helpfile(&mut self, name: &str) -> DBResult<String>;
 
-impl DB {
-    pub fn new() -> Result<Self, postgres::Error> {
-        Ok(Self {
-            conn: Client::connect("host=postgres user=postgres password=password123", NoTls)?,
-        })
-    }
+    /// What the recipe makes, if there is such a recipe
+    fn recipe(&mut self, r: &Recipe) -> DBResult<Product>;
 
-    pub fn helpfile(&mut self, name: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let it = self.conn.query(
-            "SELECT title, description \
-                FROM ennui.help \
-                WHERE $1 = ANY(hook)",
-            &[&name],
-        )?;
-
-        let title: &str = match it.get(0) {
-            Some(row) => row,
-            None => Err(DBError::NoRows)?,
-        }
-        .get(0);
+    /// The first item in the database with the given handle
+    fn item_by_handle(&mut self, handle: &str) -> DBResult<ItemRow>;
 
-        let desc: &str = match it.get(0) {
-            Some(row) => row,
-            None => Err(DBError::NoRows)?,
-        }
-        .get(1);
+    /// Every item template, by id
+    fn templates(&mut self) -> DBResult<Vec<ItemTemplate>>;
 
-        let mut ret = String::from(title);
-        ret.push_str("\n\n");
-        ret.push_str(desc);
+    /// Adds the template, replacing any with the same id
+    fn save_template(&mut self, t: &ItemTemplate) -> DBResult<()>;
 
-        Ok(ret)
-    }
-}
+    /// The account with the given name, if there's such an account. Names are not case sensitive.
+    fn account(&mut self, name: &str) -> DBResult<Option<Account>>;
 
-pub fn recipe_to_item(r: &crate::soul::recipe::Recipe) -> Result<Item, String> {
-    let mut db = match DB::new() {
-        Ok(db) => db,
-        Err(e) => return Err(format!("{}", e)),
-    };
+    /// Adds the account, replacing any with the same name
+    fn save_account(&mut self, name: &str, a: &Account) -> DBResult<()>;
 
-    let crate::soul::recipe::Recipe {
-        combat_req,
-        crafting_req,
-        exploration_req,
-    } = r;
-
-    let (combat_req, crafting_req, exploration_req) = (
-        *combat_req as i32,
-        *crafting_req as i32,
-        *exploration_req as i32,
-    );
-
-    let results = match db.conn.query(
-        "\
-        SELECT name, display, description, hook, attributes
-        FROM
-            ennui.item i
-        WHERE
-            i.itemid = (SELECT itemid FROM ennui.recipe
-            WHERE
-                crafting_req = $1 AND
-                exploration_req = $2 AND
-                combat_req = $3
-            )
-        ",
-        &[&crafting_req, &exploration_req, &combat_req],
-    ) {
-        Ok(v) => v,
-        Err(e) => return Err(format!("{}", e)),
-    };
+    /// Whatever was last saved under the given name
+    fn load(&mut self, name: &str) -> DBResult<Option<Vec<u8>>>;
 
-    if results.is_empty() {
-        return Err("no recipe found".into());
-    }
+    fn save(&mut self, name: &str, data: &[u8]) -> DBResult<()>;
 
-    let r1 = results.get(0).unwrap();
-    let name: String = r1.get(0);
-    let display: String = r1.get(1);
-    let description: String = r1.get(2);
-    let handle: Vec<String> = r1.get(3);
-    let handle = Hook(handle);
-    let attrs: Vec<i32> = r1.get(4);
+    /// Fills in the help files, items and recipes that come with the game, replacing any that
+    /// were there
+    fn seed(&mut self) -> DBResult<()>;
 
-    let mut attr: Vec<Quality> = vec![];
+    /// Every migration there is for this backend, oldest first
+    fn migrations(&self) -> &'static [Migration];
 
-    for a in attrs {
-        match a.try_into() {
-            Ok(z) => attr.push(z),
-            Err(_) => return Err("unable to convert attribute".into()),
-        }
-    }
+    /// The versions of the migrations that have been run
+    fn applied(&mut self) -> DBResult<Vec<u32>>;
+
+    /// Runs the migration and records that it was run, all or nothing
+    fn apply(&mut self, m: &Migration) -> DBResult<()>;
+
+    /// Checks that the connection still works
+    fn ping(&mut self, timeout: Duration) -> DBResult<()>;
+}
+
+/// `Product` is what a recipe makes: either an item template or an item from the database
+#[derive(Debug)]
+pub enum Product {
+    Template(u64),
+    Item(ItemRow),
+}
+
+/// `ItemRow` is an item as the database keeps it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemRow {
+    pub name: String,
+    pub display: String,
+    pub description: String,
+    pub hook: Vec<String>,
+    pub attributes: Vec<i32>,
+}
 
-    let d = DescriptionWithQualities {
-        info: Description {
+impl ItemRow {
+    /// Makes a plain holdable item
+    pub fn into_item(self) -> Result<Item, String> {
+        let ItemRow {
             name,
             display,
             description,
-            handle,
-        },
-        attr,
-    };
+            hook,
+            attributes,
+        } = self;
+
+        let mut attr: Vec<Quality> = vec![];
 
-    let z = crate::item::Item::Holdable(Box::new(d));
+        for a in attributes {
+            match a.try_into() {
+                Ok(z) => attr.push(z),
+                Err(_) => return Err("unable to convert attribute".into()),
+            }
+        }
 
-    Ok(z)
+        let d = DescriptionWithQualities {
+            info: Description {
+                name,
+                display,
+                description,
+                handle: Hook(hook),
+            },
+            attr,
+            weight: 0,
+            slot: None,
+            weapon: None,
+            food: None,
+            price: 0,
+            timer: None,
+            count: 1,
+        };
+
+        Ok(Item::Holdable(Box::new(d)))
+    }
 }
 
-#[cfg(test)]
-mod db_test {
-    use super::*;
+/// Opens the database named by `ENNUI_DB`. Anything that looks like a Postgres connection string,
+/// as in `postgres://...` or `host=postgres user=...`, is a Postgres server, and anything else is
+/// the path to a SQLite file. A database that isn't at the schema version this build expects
+/// can't be used until it's been through `ennui migrate up`.
+pub fn connect() -> DBResult<Box<dyn Storage>> {
+    open(&db_name())
+}
 
-    #[test]
-    fn db_connect() {
-        let mut db = DB::new().unwrap();
+/// Opens the database named by `ENNUI_DB` without checking its schema version, for migrating it
+pub fn connect_unchecked() -> DBResult<Box<dyn Storage>> {
+    open_unchecked(&db_name())
+}
 
-        let result = db.helpfile("look");
-        assert!(dbg!(&result).is_ok());
-        let result = result.unwrap();
-        assert!(result.starts_with("LOOK"));
+/// Opens a database and checks its schema version. A SQLite file that isn't there is an error,
+/// rather than being created empty; making one is left to `ennui migrate up`.
+pub fn open(name: &str) -> DBResult<Box<dyn Storage>> {
+    let mut db: Box<dyn Storage> = if is_postgres(name) {
+        Box::new(Postgres::connect(name)?)
+    } else {
+        Box::new(Sqlite::open_existing(name)?)
+    };
+    migrate::check(&mut *db)?;
+    Ok(db)
+}
 
-        let result2 = db.helpfile("examine");
-        assert!(dbg!(&result2).is_ok());
-        let result2 = result2.unwrap();
-        assert_eq!(result, result2);
+/// The database named by `ENNUI_DB`, or the default
+pub(crate) fn db_name() -> String {
+    std::env::var("ENNUI_DB").unwrap_or_else(|_| DEFAULT_DB.to_owned())
+}
 
-        let bad = db.helpfile("butts");
-        assert!(bad.is_err());
+pub fn open_unchecked(name: &str) -> DBResult<Box<dyn Storage>> {
+    if is_postgres(name) {
+        Ok(Box::new(Postgres::connect(name)?))
+    } else {
+        Ok(Box::new(Sqlite::open(name)?))
+    }
+}
 
-        println!("{}", result2);
+fn is_postgres(name: &str) -> bool {
+    name.starts_with("postgres") || name.contains("host=")
+}
+
+/// Makes the item a recipe produces. Recipes that name an item template make that item, and any
+/// others make a plain holdable item from the database.
+pub fn recipe_to_item(db: &mut dyn Storage, r: &Recipe, templates: &Templates) -> DBResult<Item> {
+    match db.recipe(r)? {
+        Product::Template(id) => Ok(templates.spawn(id)?),
+        Product::Item(row) => Ok(row.into_item()?),
     }
 }
+
+pub fn item_by_handle(db: &mut dyn Storage, handle: &str) -> DBResult<Item> {
+    Ok(db.item_by_handle(handle)?.into_item()?)
+}
+
+/// Whether the error is only that nothing matched
+pub fn not_found(e: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(e.downcast_ref(), Some(DBError::NoRows))
+}
+
+/// Roles are kept by name, as in `Admin`
+fn role_name(role: Role) -> DBResult<String> {
+    match serde_yaml::to_value(role)? {
+        serde_yaml::Value::String(s) => Ok(s),
+        _ => Err("unable to name role".into()),
+    }
+}
+
+fn parse_role(name: &str) -> DBResult<Role> {
+    Ok(serde_yaml::from_value(serde_yaml::Value::String(
+        name.to_owned(),
+    ))?)
+}
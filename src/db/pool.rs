@@ -0,0 +1,200 @@
+use super::{db_name, open, DBResult, Storage, DB_TIMEOUT};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// How many connections a pool keeps open at most
+pub const POOL_SIZE: usize = 4;
+
+/// Connections that have sat unused for longer than this are checked before they're handed out
+const CHECK_AFTER: Duration = Duration::from_secs(30);
+
+/// `Pool` shares a handful of connections to one database. Nothing is opened until it's needed,
+/// connections are put back for the next caller once they're done with, and any that have gone
+/// bad are thrown away and replaced.
+pub struct Pool {
+    name: String,
+    size: usize,
+    timeout: Duration,
+    state: Mutex<PoolState>,
+    freed: Condvar,
+}
+
+#[derive(Default)]
+struct PoolState {
+    idle: Vec<(Box<dyn Storage>, Instant)>,
+    open: usize,
+}
+
+impl Pool {
+    pub fn new(name: &str, size: usize, timeout: Duration) -> Self {
+        Self {
+            name: name.to_owned(),
+            size,
+            timeout,
+            state: Mutex::new(PoolState::default()),
+            freed: Condvar::new(),
+        }
+    }
+
+    /// A pool for the database named by `ENNUI_DB`
+    pub fn from_env() -> Self {
+        Self::new(&db_name(), POOL_SIZE, DB_TIMEOUT)
+    }
+
+    /// Hands out a connection, opening one if there's room for it, or otherwise waiting for one
+    /// to be put back
+    pub fn get(&self) -> DBResult<Conn<'_>> {
+        let deadline = Instant::now() + self.timeout;
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if let Some((mut db, since)) = state.idle.pop() {
+                if since.elapsed() < CHECK_AFTER {
+                    return Ok(Conn::new(self, db));
+                }
+                // checked without the lock, so nobody else waits on a slow ping
+                drop(state);
+                if db.ping(self.timeout).is_ok() {
+                    return Ok(Conn::new(self, db));
+                }
+                return self.connect();
+            }
+
+            if state.open < self.size {
+                state.open += 1;
+                drop(state);
+                return self.connect();
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err("timed out waiting for a database connection".into());
+            }
+            state = self.freed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    /// Opens a connection that's already been counted, giving its place back if it can't be
+    fn connect(&self) -> DBResult<Conn<'_>> {
+        match open(&self.name) {
+            Ok(db) => Ok(Conn::new(self, db)),
+            Err(e) => {
+                self.release();
+                Err(e)
+            }
+        }
+    }
+
+    /// Does `f` with a connection. If it fails because the connection has dropped, it's tried
+    /// once more with a fresh one.
+    pub fn run<T, F>(&self, mut f: F) -> DBResult<T>
+    where
+        F: FnMut(&mut dyn Storage) -> DBResult<T>,
+    {
+        let mut conn = self.get()?;
+        match f(&mut *conn) {
+            Err(_) if conn.ping(self.timeout).is_err() => {
+                conn.discard();
+                f(&mut *self.get()?)
+            }
+            result => result,
+        }
+    }
+
+    /// How many connections are open, whether they're in use or not
+    pub fn open_connections(&self) -> usize {
+        self.state.lock().unwrap().open
+    }
+
+    fn put_back(&self, db: Box<dyn Storage>) {
+        self.state.lock().unwrap().idle.push((db, Instant::now()));
+        self.freed.notify_one();
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().open -= 1;
+        self.freed.notify_one();
+    }
+}
+
+/// `Conn` is a connection on loan from a pool, which goes back to it when dropped
+pub struct Conn<'a> {
+    pool: &'a Pool,
+    db: Option<Box<dyn Storage>>,
+}
+
+impl<'a> Conn<'a> {
+    fn new(pool: &'a Pool, db: Box<dyn Storage>) -> Self {
+        Self { pool, db: Some(db) }
+    }
+
+    /// Closes the connection rather than putting it back
+    pub fn discard(mut self) {
+        if self.db.take().is_some() {
+            self.pool.release();
+        }
+    }
+}
+
+impl Deref for Conn<'_> {
+    type Target = dyn Storage;
+
+    fn deref(&self) -> &Self::Target {
+        &**self.db.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for Conn<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut **self.db.as_mut().unwrap()
+    }
+}
+
+impl Drop for Conn<'_> {
+    fn drop(&mut self) {
+        if let Some(db) = self.db.take() {
+            self.pool.put_back(db);
+        }
+    }
+}
+
+#[cfg(test)]
+mod pool_test {
+    use super::*;
+    use crate::db::sqlite::sqlite_test::seeded_file;
+
+    #[test]
+    fn pool_reuses_connections() {
+        let pool = Pool::new(&seeded_file("pool"), 2, Duration::from_millis(100));
+        assert_eq!(pool.open_connections(), 0);
+
+        let help = pool.run(|db| db.helpfile("look")).unwrap();
+        assert!(help.starts_with("LOOK"));
+        pool.run(|db| db.helpfile("look")).unwrap();
+        assert_eq!(pool.open_connections(), 1);
+
+        let first = pool.get().unwrap();
+        let second = pool.get().unwrap();
+        assert_eq!(pool.open_connections(), 2);
+        assert!(pool.get().is_err());
+
+        second.discard();
+        assert_eq!(pool.open_connections(), 1);
+        drop(first);
+        pool.get().unwrap();
+        assert_eq!(pool.open_connections(), 1);
+    }
+
+    #[test]
+    fn pool_reports_bad_databases() {
+        let pool = Pool::new("/nonexistent/ennui.db", 2, Duration::from_millis(100));
+        assert!(pool.run(|db| db.helpfile("look")).is_err());
+        assert_eq!(pool.open_connections(), 0);
+
+        // one that hasn't been migrated is no good either
+        let pool = Pool::new(":memory:", 2, Duration::from_millis(100));
+        assert!(pool.run(|db| db.helpfile("look")).is_err());
+        assert_eq!(pool.open_connections(), 0);
+    }
+}
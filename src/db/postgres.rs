@@ -0,0 +1,273 @@
+use super::migrate::{Migration, POSTGRES};
+use super::{Account, DBError, DBResult, ItemRow, Product, Storage, DB_TIMEOUT};
+use crate::item::template::ItemTemplate;
+use crate::soul::recipe::Recipe;
+use postgres::{Client, Config, NoTls, Row};
+use std::time::Duration;
+
+/// The server in the docker-compose setup
+pub const POSTGRES_DEFAULT: &str = "host=postgres user=postgres password=password123";
+
+/// `Postgres` keeps everything in the `ennui` schema of a Postgres server, as laid out by the
+/// migrations in `migrations/postgres` and filled in by `populate.sql`
+pub struct Postgres {
+    conn: Client,
+}
+
+impl Postgres {
+    pub fn new() -> Result<Self, postgres::Error> {
+        Self::connect(POSTGRES_DEFAULT)
+    }
+
+    /// Connects, giving up after `DB_TIMEOUT` unless the parameters give a `connect_timeout` of
+    /// their own. Queries that take longer than that are cancelled by the server.
+    pub fn connect(params: &str) -> Result<Self, postgres::Error> {
+        let mut config: Config = params.parse()?;
+        if config.get_connect_timeout().is_none() {
+            config.connect_timeout(DB_TIMEOUT);
+        }
+
+        let mut conn = config.connect(NoTls)?;
+        conn.batch_execute(&format!(
+            "SET statement_timeout = {}",
+            DB_TIMEOUT.as_millis()
+        ))?;
+        Ok(Self { conn })
+    }
+}
+
+impl Storage for Postgres {
+    fn helpfile(&mut self, name: &str) -> DBResult<String> {
+        let it = self.conn.query(
+            "SELECT title, description \
+                FROM ennui.help \
+                WHERE $1 = ANY(hook)",
+            &[&name],
+        )?;
+
+        let row = match it.get(0) {
+            Some(row) => row,
+            None => return Err(DBError::NoRows.into()),
+        };
+        let title: &str = row.get(0);
+        let desc: &str = row.get(1);
+
+        let mut ret = String::from(title);
+        ret.push_str("\n\n");
+        ret.push_str(desc);
+
+        Ok(ret)
+    }
+
+    fn recipe(&mut self, r: &Recipe) -> DBResult<Product> {
+        let Recipe {
+            combat_req,
+            crafting_req,
+            exploration_req,
+        } = r;
+
+        let (combat_req, crafting_req, exploration_req) = (
+            *combat_req as i32,
+            *crafting_req as i32,
+            *exploration_req as i32,
+        );
+
+        let results = self.conn.query(
+            "\
+            SELECT i.name, i.display, i.description, i.hook, i.attributes, r.template
+            FROM
+                ennui.recipe r
+                LEFT OUTER JOIN ennui.item i
+                    ON i.itemid = r.itemid
+            WHERE
+                r.crafting_req = $1 AND
+                r.exploration_req = $2 AND
+                r.combat_req = $3
+            ",
+            &[&crafting_req, &exploration_req, &combat_req],
+        )?;
+
+        let row = match results.get(0) {
+            Some(row) => row,
+            None => return Err(DBError::NoRows.into()),
+        };
+
+        Ok(match row.get::<_, Option<i32>>(5) {
+            Some(id) => Product::Template(id as u64),
+            None => Product::Item(row_to_item(row)),
+        })
+    }
+
+    fn item_by_handle(&mut self, handle: &str) -> DBResult<ItemRow> {
+        let results = self.conn.query(
+            "\
+            SELECT name, display, description, hook, attributes
+            FROM
+                ennui.item i
+            WHERE
+                $1 = ANY(i.hook)
+            ",
+            &[&handle],
+        )?;
+
+        match results.get(0) {
+            Some(row) => Ok(row_to_item(row)),
+            None => Err(DBError::NoRows.into()),
+        }
+    }
+
+    fn templates(&mut self) -> DBResult<Vec<ItemTemplate>> {
+        let mut ret = vec![];
+        for row in self
+            .conn
+            .query("SELECT id, item FROM ennui.template ORDER BY id", &[])?
+        {
+            let id: i64 = row.get(0);
+            let item: &str = row.get(1);
+            ret.push(ItemTemplate {
+                id: id as u64,
+                item: serde_yaml::from_str(item)?,
+            });
+        }
+        Ok(ret)
+    }
+
+    fn save_template(&mut self, t: &ItemTemplate) -> DBResult<()> {
+        let item = serde_yaml::to_string(&t.item)?;
+        self.conn.execute(
+            "INSERT INTO ennui.template (id, item) VALUES ($1, $2) \
+                ON CONFLICT (id) DO UPDATE SET item = EXCLUDED.item",
+            &[&(t.id as i64), &item],
+        )?;
+        Ok(())
+    }
+
+    fn account(&mut self, name: &str) -> DBResult<Option<Account>> {
+        let rows = self.conn.query(
+            "SELECT role, password FROM ennui.account WHERE name = $1",
+            &[&name.to_lowercase()],
+        )?;
+        match rows.get(0) {
+            Some(row) => Ok(Some(Account {
+                role: super::parse_role(row.get(0))?,
+                password: row.get(1),
+            })),
+            None => Ok(None),
+        }
+    }
+
+    fn save_account(&mut self, name: &str, a: &Account) -> DBResult<()> {
+        self.conn.execute(
+            "INSERT INTO ennui.account (name, role, password) VALUES ($1, $2, $3) \
+                ON CONFLICT (name) DO UPDATE \
+                SET role = EXCLUDED.role, password = EXCLUDED.password",
+            &[
+                &name.to_lowercase(),
+                &super::role_name(a.role)?,
+                &a.password,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load(&mut self, name: &str) -> DBResult<Option<Vec<u8>>> {
+        let rows = self
+            .conn
+            .query("SELECT data FROM ennui.save WHERE name = $1", &[&name])?;
+        Ok(rows.get(0).map(|row| row.get(0)))
+    }
+
+    fn save(&mut self, name: &str, data: &[u8]) -> DBResult<()> {
+        self.conn.execute(
+            "INSERT INTO ennui.save (name, data) VALUES ($1, $2) \
+                ON CONFLICT (name) DO UPDATE SET data = EXCLUDED.data",
+            &[&name, &data],
+        )?;
+        Ok(())
+    }
+
+    /// Postgres servers are filled in by `populate.sql`, so there's nothing to do here
+    fn seed(&mut self) -> DBResult<()> {
+        Ok(())
+    }
+
+    fn migrations(&self) -> &'static [Migration] {
+        POSTGRES
+    }
+
+    fn applied(&mut self) -> DBResult<Vec<u32>> {
+        let migrated: bool = self
+            .conn
+            .query_one(
+                "SELECT to_regclass('ennui.schema_version') IS NOT NULL",
+                &[],
+            )?
+            .get(0);
+        if !migrated {
+            return Ok(vec![]);
+        }
+
+        let rows = self
+            .conn
+            .query("SELECT version FROM ennui.schema_version", &[])?;
+        Ok(rows.iter().map(|row| row.get::<_, i32>(0) as u32).collect())
+    }
+
+    fn apply(&mut self, m: &Migration) -> DBResult<()> {
+        let mut tx = self.conn.transaction()?;
+        tx.batch_execute(
+            "CREATE SCHEMA IF NOT EXISTS ennui;
+            CREATE TABLE IF NOT EXISTS ennui.schema_version (
+                version int PRIMARY KEY,
+                name text NOT NULL,
+                applied_at timestamptz NOT NULL DEFAULT now()
+            );",
+        )?;
+        tx.batch_execute(m.sql)?;
+        tx.execute(
+            "INSERT INTO ennui.schema_version (version, name) VALUES ($1, $2)",
+            &[&(m.version as i32), &m.name],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn ping(&mut self, timeout: Duration) -> DBResult<()> {
+        Ok(self.conn.is_valid(timeout)?)
+    }
+}
+
+fn row_to_item(r1: &Row) -> ItemRow {
+    ItemRow {
+        name: r1.get(0),
+        display: r1.get(1),
+        description: r1.get(2),
+        hook: r1.get(3),
+        attributes: r1.get(4),
+    }
+}
+
+#[cfg(test)]
+mod db_test {
+    use super::*;
+
+    #[test]
+    fn db_connect() {
+        let mut db = Postgres::new().unwrap();
+
+        let result = db.helpfile("look");
+        assert!(dbg!(&result).is_ok());
+        let result = result.unwrap();
+        assert!(result.starts_with("LOOK"));
+
+        let result2 = db.helpfile("examine");
+        assert!(dbg!(&result2).is_ok());
+        let result2 = result2.unwrap();
+        assert_eq!(result, result2);
+
+        let bad = db.helpfile("butts");
+        assert!(bad.is_err());
+
+        println!("{}", result2);
+    }
+}
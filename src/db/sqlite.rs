@@ -0,0 +1,519 @@
+use super::migrate::{Migration, SQLITE};
+use super::{
+    parse_role, role_name, Account, DBError, DBResult, ItemRow, Product, Storage, DB_TIMEOUT,
+};
+use crate::item::template::ItemTemplate;
+use crate::soul::recipe::Recipe;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Transaction, NO_PARAMS};
+use std::path::Path;
+use std::time::Duration;
+
+/// `Sqlite` keeps everything in a single file, or in memory, with no server to run. Since SQLite
+/// has no arrays, hooks and attributes get tables of their own, and the help files, items and
+/// recipes are seeded from the same CSV files `populate.sql` reads.
+pub struct Sqlite {
+    conn: Connection,
+}
+
+impl Sqlite {
+    pub fn open<P: AsRef<Path>>(path: P) -> DBResult<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens a database file only if it's already there, rather than leaving an empty one behind
+    pub fn open_existing<P: AsRef<Path>>(path: P) -> DBResult<Self> {
+        let flags = OpenFlags::default() - OpenFlags::SQLITE_OPEN_CREATE;
+        Self::init(Connection::open_with_flags(path, flags)?)
+    }
+
+    /// A database that lasts only as long as it's open. It starts out empty, and has to be
+    /// migrated and seeded before it's any use.
+    pub fn memory() -> DBResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> DBResult<Self> {
+        conn.busy_timeout(DB_TIMEOUT)?;
+        Ok(Self { conn })
+    }
+
+    /// Replaces the help files, items and recipes with the ones in the CSV files
+    fn populate(&mut self) -> DBResult<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(
+            "DELETE FROM recipe;
+            DELETE FROM item_attribute;
+            DELETE FROM item_hook;
+            DELETE FROM item;
+            DELETE FROM help_hook;
+            DELETE FROM help;",
+        )?;
+
+        for row in parse_csv(include_str!("../../populate.csv")) {
+            if let [title, hook, description] = &row[..] {
+                tx.execute(
+                    "INSERT INTO help (title, description) VALUES (?1, ?2)",
+                    params![title, description],
+                )?;
+                let id = tx.last_insert_rowid();
+                for h in parse_array(hook) {
+                    tx.execute(
+                        "INSERT INTO help_hook (helpid, hook) VALUES (?1, ?2)",
+                        params![id, h],
+                    )?;
+                }
+            }
+        }
+
+        for row in parse_csv(include_str!("../../populate_item.csv")) {
+            if let [name, display, description, hook, attributes] = &row[..] {
+                tx.execute(
+                    "INSERT INTO item (name, display, description) VALUES (?1, ?2, ?3)",
+                    params![name, display, description],
+                )?;
+                let id = tx.last_insert_rowid();
+                for h in parse_array(hook) {
+                    tx.execute(
+                        "INSERT INTO item_hook (itemid, hook) VALUES (?1, ?2)",
+                        params![id, h],
+                    )?;
+                }
+                for a in parse_array(attributes) {
+                    tx.execute(
+                        "INSERT INTO item_attribute (itemid, attribute) VALUES (?1, ?2)",
+                        params![id, a.parse::<i32>()?],
+                    )?;
+                }
+            }
+        }
+
+        for row in parse_csv(include_str!("../../populate_recipe.csv")) {
+            if let [name, crafting, exploration, combat, template] = &row[..] {
+                insert_recipe(&tx, name, [crafting, exploration, combat], template)?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn item_row(&self, itemid: i64) -> DBResult<ItemRow> {
+        let (name, display, description) = self.conn.query_row(
+            "SELECT name, display, description FROM item WHERE itemid = ?1",
+            params![itemid],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let hook = self
+            .conn
+            .prepare("SELECT hook FROM item_hook WHERE itemid = ?1 ORDER BY rowid")?
+            .query_map(params![itemid], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        let attributes = self
+            .conn
+            .prepare("SELECT attribute FROM item_attribute WHERE itemid = ?1 ORDER BY rowid")?
+            .query_map(params![itemid], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(ItemRow {
+            name,
+            display,
+            description,
+            hook,
+            attributes,
+        })
+    }
+}
+
+fn insert_recipe(tx: &Transaction, name: &str, reqs: [&String; 3], template: &str) -> DBResult<()> {
+    let itemid: Option<i64> = tx
+        .query_row(
+            "SELECT itemid FROM item WHERE name = ?1",
+            params![name],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let template = match template.trim() {
+        "" => None,
+        t => Some(t.parse::<i64>()?),
+    };
+
+    tx.execute(
+        "INSERT INTO recipe (itemid, crafting_req, exploration_req, combat_req, template) \
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            itemid,
+            reqs[0].trim().parse::<i64>()?,
+            reqs[1].trim().parse::<i64>()?,
+            reqs[2].trim().parse::<i64>()?,
+            template
+        ],
+    )?;
+    Ok(())
+}
+
+impl Storage for Sqlite {
+    fn helpfile(&mut self, name: &str) -> DBResult<String> {
+        let found: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT title, description \
+                    FROM help h JOIN help_hook k ON k.helpid = h.helpid \
+                    WHERE k.hook = ?1 \
+                    ORDER BY h.helpid",
+                params![name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let (title, desc) = match found {
+            Some(found) => found,
+            None => return Err(DBError::NoRows.into()),
+        };
+
+        let mut ret = title;
+        ret.push_str("\n\n");
+        ret.push_str(&desc);
+
+        Ok(ret)
+    }
+
+    fn recipe(&mut self, r: &Recipe) -> DBResult<Product> {
+        let Recipe {
+            combat_req,
+            crafting_req,
+            exploration_req,
+        } = r;
+
+        let found: Option<(Option<i64>, Option<i64>)> = self
+            .conn
+            .query_row(
+                "SELECT itemid, template FROM recipe \
+                    WHERE crafting_req = ?1 AND exploration_req = ?2 AND combat_req = ?3 \
+                    ORDER BY recid",
+                params![
+                    *crafting_req as i64,
+                    *exploration_req as i64,
+                    *combat_req as i64
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        match found {
+            Some((_, Some(id))) => Ok(Product::Template(id as u64)),
+            Some((Some(itemid), None)) => Ok(Product::Item(self.item_row(itemid)?)),
+            _ => Err(DBError::NoRows.into()),
+        }
+    }
+
+    fn item_by_handle(&mut self, handle: &str) -> DBResult<ItemRow> {
+        let itemid: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT itemid FROM item_hook WHERE hook = ?1 ORDER BY itemid",
+                params![handle],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match itemid {
+            Some(itemid) => self.item_row(itemid),
+            None => Err(DBError::NoRows.into()),
+        }
+    }
+
+    fn templates(&mut self) -> DBResult<Vec<ItemTemplate>> {
+        let rows = self
+            .conn
+            .prepare("SELECT id, item FROM template ORDER BY id")?
+            .query_map(NO_PARAMS, |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ret = vec![];
+        for (id, item) in rows {
+            ret.push(ItemTemplate {
+                id: id as u64,
+                item: serde_yaml::from_str(&item)?,
+            });
+        }
+        Ok(ret)
+    }
+
+    fn save_template(&mut self, t: &ItemTemplate) -> DBResult<()> {
+        let item = serde_yaml::to_string(&t.item)?;
+        self.conn.execute(
+            "INSERT INTO template (id, item) VALUES (?1, ?2) \
+                ON CONFLICT (id) DO UPDATE SET item = excluded.item",
+            params![t.id as i64, item],
+        )?;
+        Ok(())
+    }
+
+    fn account(&mut self, name: &str) -> DBResult<Option<Account>> {
+        let row: Option<(String, String)> = self
+            .conn
+            .query_row(
+                "SELECT role, password FROM account WHERE name = ?1",
+                params![name.to_lowercase()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        match row {
+            Some((role, password)) => Ok(Some(Account {
+                role: parse_role(&role)?,
+                password,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    fn save_account(&mut self, name: &str, a: &Account) -> DBResult<()> {
+        self.conn.execute(
+            "INSERT INTO account (name, role, password) VALUES (?1, ?2, ?3) \
+                ON CONFLICT (name) DO UPDATE SET role = excluded.role, password = excluded.password",
+            params![name.to_lowercase(), role_name(a.role)?, a.password],
+        )?;
+        Ok(())
+    }
+
+    fn load(&mut self, name: &str) -> DBResult<Option<Vec<u8>>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT data FROM save WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn save(&mut self, name: &str, data: &[u8]) -> DBResult<()> {
+        self.conn.execute(
+            "INSERT INTO save (name, data) VALUES (?1, ?2) \
+                ON CONFLICT (name) DO UPDATE SET data = excluded.data",
+            params![name, data],
+        )?;
+        Ok(())
+    }
+
+    fn seed(&mut self) -> DBResult<()> {
+        self.populate()
+    }
+
+    fn migrations(&self) -> &'static [Migration] {
+        SQLITE
+    }
+
+    fn applied(&mut self) -> DBResult<Vec<u32>> {
+        let migrated: bool = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master \
+                WHERE type = 'table' AND name = 'schema_version')",
+            NO_PARAMS,
+            |row| row.get(0),
+        )?;
+        if !migrated {
+            return Ok(vec![]);
+        }
+
+        Ok(self
+            .conn
+            .prepare("SELECT version FROM schema_version")?
+            .query_map(NO_PARAMS, |row| row.get(0))?
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn apply(&mut self, m: &Migration) -> DBResult<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );",
+        )?;
+        tx.execute_batch(m.sql)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name) VALUES (?1, ?2)",
+            params![m.version, m.name],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn ping(&mut self, _timeout: Duration) -> DBResult<()> {
+        Ok(self.conn.query_row("SELECT 1", NO_PARAMS, |_| Ok(()))?)
+    }
+}
+
+/// Splits CSV text, as Postgres writes it, into rows of fields. Quoted fields may hold commas and
+/// newlines, and `""` is a quote.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' if !quoted => (),
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+    rows
+}
+
+/// Reads a Postgres array literal, as in `{look, examine}`
+fn parse_array(text: &str) -> Vec<String> {
+    text.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .map(|s| s.trim().to_owned())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+pub mod sqlite_test {
+    use super::*;
+    use crate::db::migrate;
+    use crate::describe::Describe;
+    use crate::item::YamlItem;
+    use crate::player::Role;
+
+    /// A database in memory, migrated and seeded
+    pub fn seeded() -> Sqlite {
+        let mut db = Sqlite::memory().unwrap();
+        migrate::up(&mut db).unwrap();
+        db.seed().unwrap();
+        db
+    }
+
+    /// A database in a fresh file under the temp directory, migrated and seeded, for tests that
+    /// open more than one connection to it
+    pub fn seeded_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("ennui-{}-{}.db", name, std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let mut db = Sqlite::open(&path).unwrap();
+        migrate::up(&mut db).unwrap();
+        db.seed().unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn sqlite_open_missing() {
+        let path = std::env::temp_dir().join(format!("ennui-missing-{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        assert!(crate::db::open(path.to_str().unwrap()).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn sqlite_helpfile() {
+        let mut db = seeded();
+
+        let result = db.helpfile("look").unwrap();
+        assert!(result.starts_with("LOOK"));
+        assert_eq!(result, db.helpfile("examine").unwrap());
+        assert!(db.helpfile("commands").unwrap().starts_with("COMMANDS"));
+        assert!(db.helpfile("butts").is_err());
+    }
+
+    #[test]
+    fn sqlite_items_and_recipes() {
+        let mut db = seeded();
+
+        let belt = db.item_by_handle("belt").unwrap();
+        assert_eq!(belt.name, "cumberbund");
+        assert_eq!(belt.hook, vec!["cumberbund", "belt"]);
+        assert_eq!(belt.attributes, vec![1]);
+        assert!(db.item_by_handle("butts").is_err());
+
+        let hat = Recipe {
+            combat_req: 0,
+            crafting_req: 0,
+            exploration_req: 1,
+        };
+        match db.recipe(&hat).unwrap() {
+            Product::Item(row) => assert_eq!(row.into_item().unwrap().name(), "hat"),
+            p => panic!("expected an item, got {:?}", p),
+        }
+
+        let sword = Recipe {
+            combat_req: 1,
+            crafting_req: 0,
+            exploration_req: 0,
+        };
+        assert!(matches!(db.recipe(&sword).unwrap(), Product::Template(1)));
+        assert!(db.recipe(&Recipe::default()).is_err());
+    }
+
+    #[test]
+    fn sqlite_templates_accounts_and_saves() {
+        let mut db = seeded();
+        assert!(db.templates().unwrap().is_empty());
+
+        let item: YamlItem = serde_yaml::from_str(
+            "Holdable:\n  name: rock\n  display: a rock\n  description: just a rock\n  handle: [rock]\n",
+        )
+        .unwrap();
+        db.save_template(&ItemTemplate { id: 3, item }).unwrap();
+        let templates = db.templates().unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].id, 3);
+        assert!(matches!(&templates[0].item, YamlItem::Holdable(d) if d.name() == "rock"));
+
+        assert_eq!(db.account("Peter").unwrap(), None);
+        db.save_account("Peter", &Account::new(Role::Builder, "one"))
+            .unwrap();
+        db.save_account("peter", &Account::new(Role::Admin, "two"))
+            .unwrap();
+        let account = db.account("PETER").unwrap().unwrap();
+        assert_eq!(account.role, Role::Admin);
+        assert!(account.check("two"));
+
+        assert_eq!(db.load("world").unwrap(), None);
+        db.save("world", b"one").unwrap();
+        db.save("world", b"two").unwrap();
+        assert_eq!(db.load("world").unwrap(), Some(b"two".to_vec()));
+    }
+
+    #[test]
+    fn csv() {
+        let rows = parse_csv("a,\"{b, c\"},\"d\n\"\"e\"\"\"\nf,,\n");
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a".to_owned(), "{b, c}".into(), "d\n\"e\"".into()],
+                vec!["f".to_owned(), "".into(), "".into()],
+            ]
+        );
+        assert_eq!(parse_array("{b, c}"), vec!["b", "c"]);
+    }
+}
@@ -9,7 +9,7 @@ pub trait Describe: Send + Sync + Debug {
     fn handle(&self) -> Hook;
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Description {
     pub name: String,
     pub display: String,
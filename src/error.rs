@@ -8,8 +8,10 @@ pub enum CmdErr {
     TooHeavy,
     Guarded,
     NotClothing,
+    NotWeapon,
     PlayerNotFound,
     CannotAcceptGivenItem,
+    NotEnough,
 }
 
 #[derive(Debug)]
@@ -21,6 +23,7 @@ pub enum EnnuiError {
     Msg(String),
     NoneFound(std::option::NoneError),
     Quit,
+    Shutdown,
 }
 
 impl Display for EnnuiError {
@@ -11,8 +11,9 @@ use crate::fight::Starter::{Aggressor, Defender};
 use crate::error::CmdErr::PlayerNotFound;
 use crate::error::EnnuiError;
 use crate::error::EnnuiError::Simple;
+use crate::item::weapon::Dice;
 use crate::player::list::PlayerIdList;
-use crate::player::PlayerStatus::Dead;
+use crate::player::PlayerStatus::{Dead, Fighting};
 use crate::text::channel::DiscreteMessage;
 use crate::text::message::{FightAudience, Message, MessageFormat};
 use crate::text::Color::{Red, Yellow};
@@ -239,6 +240,21 @@ fn extract(fight: &Arc<Mutex<BasicFight>>, player: Starter) -> (Player, u128) {
 }
 
 fn handle_fight(
+    fight: &mut Arc<Mutex<BasicFight>>,
+    delay: Duration,
+    fight_sender: &Sender<(FightAudience, FightMessage)>,
+    mut pa: Player,
+    aid: u128,
+    mut pb: Player,
+    bid: u128,
+) -> Result<(), String> {
+    let res = fight_rounds(fight, delay, fight_sender, pa.clone(), aid, pb.clone(), bid);
+    pa.unset_attr(Fighting);
+    pb.unset_attr(Fighting);
+    res
+}
+
+fn fight_rounds(
     mut fight: &mut Arc<Mutex<BasicFight>>,
     delay: Duration,
     fight_sender: &Sender<(FightAudience, FightMessage)>,
@@ -282,9 +298,8 @@ fn handle_fight(
             &mut fight,
             &fight_sender,
             fight_audience,
-            &pa.name(),
-            &pb.name(),
             pa.clone(),
+            pb.clone(),
             Aggressor,
         )?;
 
@@ -313,9 +328,8 @@ fn handle_fight(
             &mut fight,
             &fight_sender,
             fight_audience,
-            &pb.name(),
-            &pa.name(),
             pb.clone(),
+            pa.clone(),
             Defender,
         )?;
 
@@ -334,15 +348,17 @@ fn fight_logic(
     cl: &mut Arc<Mutex<BasicFight>>,
     fight_sender: &Sender<(FightAudience, FightMessage)>,
     fight_audience: FightAudience,
-    a_name: &str,
-    b_name: &str,
-    player: Player,
+    attacker: Player,
+    defender: Player,
     starter: Starter,
 ) -> Result<(), String> {
-    let mut player = player.lock().unwrap();
-    if let ConnectionStatus::Disconnected = player.is_connected() {
-        cl.end();
-    }
+    let (attack, a_name) = {
+        let attacker = attacker.lock().unwrap();
+        if let ConnectionStatus::Disconnected = attacker.is_connected() {
+            cl.end();
+        }
+        (Attack::of(&attacker), attacker.name())
+    };
 
     let (before, after) = match starter {
         Aggressor => ("\n\n", ""),
@@ -350,24 +366,36 @@ fn fight_logic(
     };
 
     let aid = fight_audience.1;
+    let (verb, verbs) = attack.verbs;
+
+    let mut player = defender.lock().unwrap();
+    let b_name = player.name();
+    let mut strikes = 0;
+    for _ in 0..attack.speed {
+        player.hurt(attack.roll());
+        strikes += 1;
+        if player.hp() <= 0 {
+            break;
+        }
+    }
 
-    player.hurt(25);
+    let lines = |s: String| vec![s; strikes].join("\n");
     fight_sender
         .send((
             fight_audience,
             FightMessage {
-                s: format!("you hit {}", b_name)
+                s: lines(format!("you {} {}", verb, b_name))
                     .color(Yellow)
                     .custom_padded(before, after)
                     .into(),
                 obj: Some(
-                    format!("{} hits you", a_name)
+                    lines(format!("{} {} you", a_name, verbs))
                         .color(Red)
                         .custom_padded(before, after)
                         .into(),
                 ),
                 oth: Some(
-                    format!("{} hits {}", a_name, b_name)
+                    lines(format!("{} {} {}", a_name, verbs, b_name))
                         .custom_padded(before, after)
                         .into(),
                 ),
@@ -395,3 +423,49 @@ fn fight_logic(
 
     Ok(())
 }
+
+/// Damage done by a player with nothing in hand
+const UNARMED: Dice = Dice {
+    count: 1,
+    sides: 4,
+    bonus: 0,
+};
+
+/// Strength and dexterity above this add to damage, and below it take away
+const AVERAGE_STAT: i64 = 10;
+
+/// `Attack` is how hard and how often a player strikes each round, worked out from what they're
+/// wielding and how strong and nimble they are
+struct Attack {
+    damage: Dice,
+    bonus: i64,
+    verbs: (&'static str, &'static str),
+    speed: u32,
+}
+
+impl Attack {
+    fn of(player: &BarePlayer) -> Self {
+        let bonus =
+            (player.strength() - AVERAGE_STAT) / 2 + (player.dexterity() - AVERAGE_STAT) / 4;
+
+        match player.wielded() {
+            Some(w) => Self {
+                damage: w.damage,
+                bonus,
+                verbs: w.kind.verbs(),
+                speed: w.speed.max(1),
+            },
+            None => Self {
+                damage: UNARMED,
+                bonus,
+                verbs: ("hit", "hits"),
+                speed: 1,
+            },
+        }
+    }
+
+    /// Every blow that lands does at least a little damage
+    fn roll(&self) -> usize {
+        (self.damage.roll() + self.bonus).max(1) as usize
+    }
+}
@@ -0,0 +1,396 @@
+use super::*;
+use crate::db::{item_by_handle, not_found};
+use crate::game::util::npc_templates;
+use std::convert::TryInto;
+use std::time::{Duration, Instant};
+
+pub fn fill_interpreter(i: &mut Interpreter) {
+    i.insert("goto", |g, u, a| {
+        let dest: Coord = match a.try_into() {
+            Ok(c) => c,
+            Err(_) => return message(u, "Usage: goto <x> <y> [z]"),
+        };
+
+        match g.teleport(u, dest) {
+            Ok(()) => (),
+            Err(EnnuiError::Msg(m)) => return message(u, m),
+            Err(e) => return Err(e),
+        }
+
+        message(u, g.describe_room(u)?)
+    });
+
+    i.insert("transfer", |g, u, a| {
+        if a.len() != 1 {
+            return message(u, "Usage: transfer <player>");
+        }
+
+        let other = match g.id_of(a[0]) {
+            Some(id) => id,
+            None => return message(u, "there's no-one by that name in the game"),
+        };
+
+        let loc = g.loc_of(u)?;
+        g.teleport(other, loc)?;
+
+        let name = g.name_of(u)?;
+        let room = g.describe_room(other)?;
+        g.send(
+            &other,
+            &format!("{} has summoned you\n\n{}", name, room).padded(),
+        );
+
+        message(u, format!("you summon {}", g.name_of(other)?))
+    });
+
+    i.insert("force", |g, u, a| {
+        if a.len() < 2 {
+            return message(u, "Usage: force <player> <command>");
+        }
+
+        let other = match g.id_of(a[0]) {
+            Some(id) => id,
+            None => return message(u, "there's no-one by that name in the game"),
+        };
+
+        if other == u {
+            return message(u, "just do it yourself");
+        }
+
+        let role = g.get_player(u)?.lock().unwrap().role();
+        let other_role = g.get_player(other)?.lock().unwrap().role();
+        if other_role >= role {
+            return message(u, "you can't force them to do anything");
+        }
+
+        let command = a[1..].join(" ");
+        let name = g.name_of(u)?;
+        g.send(
+            &other,
+            &format!("{} forces you to '{}'", name, command).padded(),
+        );
+
+        match g.interpret(other, &command) {
+            Ok((aud, msg)) => {
+                g.send(&*aud, &*msg);
+            }
+            Err(EnnuiError::Quit) => {
+                g.remove_player(other);
+            }
+            Err(e) => return Err(e),
+        }
+
+        message(u, format!("you force {} to '{}'", a[0], command))
+    });
+
+    i.insert("load", |g, u, a| {
+        let (kind, handle) = match a {
+            [kind, handle] => (*kind, *handle),
+            _ => return message(u, "Usage: load <item/npc> <name>"),
+        };
+
+        let loc = g.loc_of(u)?;
+
+        match kind {
+            "item" => {
+                // items may be loaded from their template id, or by name from the database
+                let item = match handle.parse::<u64>() {
+                    Ok(id) => match g.templates.spawn(id) {
+                        Ok(i) => i,
+                        Err(_) => return message(u, format!("there's no item with id {}", id)),
+                    },
+                    Err(_) => {
+                        let handle = handle.to_owned();
+                        let sender = g
+                            .discrete_sender
+                            .clone()
+                            .ok_or_else(|| fatal("UNABLE TO FIND DISCRETE SENDER"))?;
+
+                        g.in_background(u, move |db, _| {
+                            let item = match item_by_handle(db, &handle) {
+                                Ok(i) => i,
+                                Err(e) if not_found(&*e) => {
+                                    return Ok(format!("there's no item called {}", handle))
+                                }
+                                Err(e) => return Err(e),
+                            };
+
+                            let name = item.name();
+                            sender
+                                .send(DiscreteMessage::PlaceItem(loc, Box::new(item)))
+                                .map_err(|_| "UNABLE TO PLACE LOADED ITEM")?;
+                            Ok(format!("you create {}", article(&name)))
+                        })?;
+                        return no_message();
+                    }
+                };
+
+                let name = item.name();
+                if g.get_room_mut(loc)?.insert_item(item).is_err() {
+                    return Err(fatal("UNABLE TO PLACE LOADED ITEM"));
+                }
+
+                message(u, format!("you create {}", article(&name)))
+            }
+            "npc" => {
+                let templates =
+                    npc_templates(&g.templates).map_err(|e| fatal(&format!("{}", e)))?;
+                let mut template = match templates.into_iter().find(|t| handle == t.info.handle) {
+                    Some(t) => t,
+                    None => return message(u, format!("there's no npc called {}", handle)),
+                };
+
+                template.loc = loc;
+                let name = template.info.name();
+
+                g.discrete_sender
+                    .as_ref()
+                    .ok_or_else(|| fatal("UNABLE TO FIND DISCRETE SENDER"))?
+                    .send(DiscreteMessage::SpawnNpc(Box::new(template.into())))
+                    .map_err(|_| fatal("UNABLE TO SPAWN NPC"))?;
+
+                message(u, format!("you create {}", name))
+            }
+            _ => message(u, "Usage: load <item/npc> <name>"),
+        }
+    });
+
+    i.insert("purge", |g, u, a| {
+        let loc = g.loc_of(u)?;
+
+        let msg = match a.len() {
+            0 => {
+                let npcs: Vec<u128> = g
+                    .rooms
+                    .player_ids(loc)
+                    .into_iter()
+                    .filter(|id| g.is_npc(*id))
+                    .collect();
+
+                for id in npcs {
+                    g.purge_npc(id)?;
+                }
+                g.get_room_mut(loc)?.take_items();
+
+                "you purge the room".to_owned()
+            }
+            1 => {
+                let handle = a[0];
+                match g.id_of_in(loc, handle) {
+                    Some(id) if g.is_npc(id) => {
+                        let name = g.name_of(id)?;
+                        g.purge_npc(id)?;
+                        format!("you purge {}", name)
+                    }
+                    Some(_) => "you can only purge npcs".to_owned(),
+                    None => match g.get_room_mut(loc)?.get_item_owned(handle.into()) {
+                        Ok(item) => format!("you purge {}", article(&item.name())),
+                        Err(_) => format!("you don't see {} here", article(handle)),
+                    },
+                }
+            }
+            _ => "Usage: purge [target]".to_owned(),
+        };
+
+        message(u, msg)
+    });
+
+    i.insert("restore", |g, u, a| {
+        let other = match a.len() {
+            0 => u,
+            1 => match g.id_of(a[0]) {
+                Some(id) => id,
+                None => return message(u, "there's no-one by that name in the game"),
+            },
+            _ => return message(u, "Usage: restore [player]"),
+        };
+
+        g.get_player(other)?.lock().unwrap().restore();
+
+        if other == u {
+            return message(u, "you feel completely restored");
+        }
+
+        let name = g.name_of(u)?;
+        g.send(&other, &format!("{} has restored you", name).padded());
+        message(u, format!("you restore {}", g.name_of(other)?))
+    });
+
+    i.insert("wizinvis", |g, u, _| {
+        let mut p = g.get_player(u)?;
+
+        if p.is(WizInvis) {
+            p.unset_attr(WizInvis);
+            message(u, "you fade back into view")
+        } else {
+            p.set_attr(WizInvis);
+            message(u, "you vanish from sight")
+        }
+    });
+
+    i.insert("silence", |g, u, a| {
+        let (name, minutes) = match a {
+            [name, minutes] => match minutes.parse::<u64>() {
+                Ok(m) => (*name, m),
+                Err(_) => return message(u, "Usage: silence <player> <minutes>"),
+            },
+            _ => return message(u, "Usage: silence <player> <minutes>"),
+        };
+
+        let other = match g.id_of(name) {
+            Some(id) => id,
+            None => return message(u, "there's no-one by that name in the game"),
+        };
+
+        let until = match minutes {
+            0 => None,
+            m => match m
+                .checked_mul(60)
+                .and_then(|s| Instant::now().checked_add(Duration::from_secs(s)))
+            {
+                Some(until) => Some(until),
+                None => return message(u, "Usage: silence <player> <minutes>"),
+            },
+        };
+        g.get_player(other)?.lock().unwrap().silence(until);
+
+        let other_name = g.name_of(other)?;
+        if until.is_none() {
+            g.send(&other, &"you may speak on the channels again".padded());
+            return message(u, format!("you lift the silence on {}", other_name));
+        }
+
+        g.send(
+            &other,
+            &format!("you have been silenced for {} minutes", minutes).padded(),
+        );
+        message(
+            u,
+            format!("you silence {} for {} minutes", other_name, minutes),
+        )
+    });
+
+    i.insert("shutdown", |_, _, _| Err(EnnuiError::Shutdown));
+}
+
+impl Game {
+    fn teleport(&mut self, u: u128, dest: Coord) -> Result<(), EnnuiError> {
+        if !self.rooms.contains_key(&dest) {
+            return Err(EnnuiError::Msg(
+                "there's nothing at those coordinates".into(),
+            ));
+        }
+
+        let loc = self.loc_of(u)?;
+        let name = self.name_of(u)?;
+        let invisible = self.get_player(u)?.is(WizInvis);
+
+        self.get_room_mut(loc)?.players_mut().remove(&u);
+        Self::do_player_move(&mut self.players, u, Some(dest), &mut self.rooms)
+            .map_err(|_| fatal("UNABLE TO TELEPORT PLAYER"))?;
+
+        if !invisible {
+            let departed = self.rooms.player_ids(loc);
+            let arrived = self.rooms.player_ids(dest).except(u);
+            self.send(
+                &departed,
+                &format!("{} vanishes in a puff of smoke", name).padded(),
+            );
+            self.send(
+                &arrived,
+                &format!("{} appears in a puff of smoke", name).padded(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn is_npc(&self, id: u128) -> bool {
+        match self.players.get(&id) {
+            Some(p) => matches!(&*p.lock().unwrap(), PlayerType::Npc(_)),
+            None => false,
+        }
+    }
+
+    fn purge_npc(&mut self, id: u128) -> Result<(), EnnuiError> {
+        let loc = self.loc_of(id)?;
+        let npc = self
+            .players
+            .remove(&id)
+            .ok_or_else(|| fatal("UNABLE TO FIND NPC"))?;
+        self.get_room_mut(loc)?.players_mut().remove(&id);
+
+        if let PlayerType::Npc(ref mut npc) = *npc.lock().unwrap() {
+            npc.stop();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod admin_test {
+    use super::*;
+    use crate::game::game_test::game_with_player;
+    use crate::player::Player;
+
+    #[test]
+    fn test_verify_role() {
+        let (mut g, u) = game_with_player(&[]);
+        let (_, msg) = g.interpret(u, "goto 0 1").unwrap();
+        assert!(msg.to_self().contains("you don't have permission"));
+
+        // taking a name gives no role of its own
+        g.set_player_name(u, "bob").unwrap();
+        let (_, msg) = g.interpret(u, "goto 0 1").unwrap();
+        assert!(msg.to_self().contains("you don't have permission"));
+
+        g.set_player_role(u, Role::Builder).unwrap();
+        g.interpret(u, "goto 0 1").unwrap();
+        assert_eq!(g.players_mut().get(&u).unwrap().loc(), Coord(0, 1, 0));
+
+        let (_, msg) = g.interpret(u, "shutdown").unwrap();
+        assert!(msg.to_self().contains("you don't have permission"));
+    }
+
+    #[test]
+    fn test_force_nests() {
+        let (mut g, u) = game_with_player(&[]);
+        for name in &["bob", "alice"] {
+            let mut p = PlayerType::Human(Player::new());
+            p.set_name(name);
+            g.add_player(p);
+        }
+        g.set_player_name(u, "peter").unwrap();
+        g.set_player_role(u, Role::Admin).unwrap();
+        let bob = g.id_of("bob").unwrap();
+        g.set_player_role(bob, Role::Builder).unwrap();
+
+        let (_, msg) = g.interpret(u, "force bob force alice look").unwrap();
+        assert!(msg
+            .to_self()
+            .contains("you force bob to 'force alice look'"));
+
+        g.interpret(u, "force bob goto 0 1").unwrap();
+        assert_eq!(g.loc_of(bob).unwrap(), Coord(0, 1, 0));
+    }
+
+    #[test]
+    fn test_silence_overflow() {
+        let (mut g, u) = game_with_player(&[]);
+        g.set_player_role(u, Role::Admin).unwrap();
+        let mut p = PlayerType::Human(Player::new());
+        p.set_name("bob");
+        g.add_player(p);
+
+        let huge = format!("silence bob {}", u64::MAX);
+        let (_, msg) = g.interpret(u, &huge).unwrap();
+        assert!(msg.to_self().contains("Usage: silence"));
+
+        let (_, msg) = g.interpret(u, "silence bob 5").unwrap();
+        assert!(msg.to_self().contains("you silence bob for 5 minutes"));
+
+        let (_, msg) = g.interpret(u, "goto north").unwrap();
+        assert!(msg.to_self().contains("Usage: goto <x> <y> [z]"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::describe::Describe;
 use crate::game::Game;
 use crate::text::message::{Broadcast, Message, MessageFormat, Messenger};
 use crate::text::Color::Green;
@@ -31,16 +32,29 @@ where
         eprintln!("this is the message going out to others: {:?}", other_msg);
         eprintln!("in file {} on line number {}", file!(), line!());
 
+        // nobody hears from a player they are ignoring
+        let sender = g.players.get(&self_id).map(|p| p.name());
+        let ignored = |g: &Game, id: u128| match (&sender, g.players.get(&id)) {
+            (Some(name), Some(p)) => p.lock().unwrap().ignores(name),
+            _ => false,
+        };
+
         send_to_single_player(g, &mut v, self_id, self_msg);
 
         if let Some(msg) = obj_msg {
-            let obj_msg = msg.wrap(90);
-            send_to_single_player(g, &mut v, object_id, obj_msg);
+            if !ignored(g, object_id) {
+                let obj_msg = msg.wrap(90);
+                send_to_single_player(g, &mut v, object_id, obj_msg);
+            }
         }
 
         if let Some(msg) = other_msg {
             let msg = msg.wrap(90);
             for id in other_ids {
+                if ignored(g, id) {
+                    continue;
+                }
+
                 if let Some(p) = g.players.get_mut(&id) {
                     v.push((id, p.lock().unwrap().write(msg.as_bytes())));
                 }
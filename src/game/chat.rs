@@ -0,0 +1,226 @@
+use super::*;
+use crate::game::util::random_insult;
+use crate::player::list::PlayerIdList;
+
+pub fn fill_interpreter(i: &mut Interpreter) {
+    i.insert_kind(CommandKind::Channel, |g, u, a| {
+        let channel = match a.first().and_then(|w| g.find_channel(u, w)) {
+            Some(c) => c.name.clone(),
+            None => return message(u, random_insult()),
+        };
+
+        g.use_channel(u, &channel, &a[1..])
+    });
+
+    i.insert("chat", |g, u, a| g.use_channel(u, "gossip", a));
+
+    i.insert("channels", |g, u, _| {
+        let p = g.get_player(u)?;
+        let p = p.lock().unwrap();
+        let role = p.role();
+
+        let mut ret = String::from("CHANNELS\n--------");
+        for c in g.channels.iter().filter(|c| role >= c.role) {
+            let status = if !p.is_subscribed(&c.name) {
+                "off"
+            } else if p.is_muted(&c.name) {
+                "muted"
+            } else {
+                "on"
+            };
+            ret.push_str(&format!("\n{:<10}{}", c.name, status));
+        }
+
+        message(u, ret)
+    });
+
+    i.insert("subscribe", |g, u, a| {
+        let channel = match a {
+            [word] => match g.find_channel(u, word) {
+                Some(c) => c.name.clone(),
+                None => return message(u, format!("there's no channel called {}", word)),
+            },
+            _ => return message(u, "Usage: subscribe <channel>"),
+        };
+
+        let subscribed = g
+            .get_player(u)?
+            .lock()
+            .unwrap()
+            .toggle_subscription(&channel);
+        let msg = if subscribed {
+            format!("you subscribe to {}", channel)
+        } else {
+            format!("you unsubscribe from {}", channel)
+        };
+
+        message(u, msg)
+    });
+
+    i.insert("mute", |g, u, a| {
+        let channel = match a {
+            [word] => match g.find_channel(u, word) {
+                Some(c) => c.name.clone(),
+                None => return message(u, format!("there's no channel called {}", word)),
+            },
+            _ => return message(u, "Usage: mute <channel>"),
+        };
+
+        let msg = if g.get_player(u)?.lock().unwrap().toggle_mute(&channel) {
+            format!("you mute {}", channel)
+        } else {
+            format!("you unmute {}", channel)
+        };
+
+        message(u, msg)
+    });
+
+    i.insert("ignore", |g, u, a| {
+        let p = g.get_player(u)?;
+        let mut p = p.lock().unwrap();
+
+        let msg = match a {
+            [] if p.ignoring().is_empty() => "you aren't ignoring anyone".to_owned(),
+            [] => format!("you are ignoring: {}", p.ignoring().join(", ")),
+            [name] if p.name().to_lowercase() == *name => "you can't ignore yourself".to_owned(),
+            [name] => {
+                if p.toggle_ignore(name) {
+                    format!("you are now ignoring {}", name)
+                } else {
+                    format!("you stop ignoring {}", name)
+                }
+            }
+            _ => "Usage: ignore [player]".to_owned(),
+        };
+
+        message(u, msg)
+    });
+}
+
+impl Game {
+    /// Speaks on a channel, or shows its history if the only argument is `history`
+    fn use_channel(
+        &mut self,
+        u: u128,
+        channel: &str,
+        a: &[&str],
+    ) -> Result<GameOutput, EnnuiError> {
+        let (subscribed, muted, silenced) = {
+            let p = self.get_player(u)?;
+            let p = p.lock().unwrap();
+            (
+                p.is_subscribed(channel),
+                p.is_muted(channel),
+                p.is_silenced(),
+            )
+        };
+
+        if !subscribed {
+            return message(u, format!("you aren't subscribed to {}", channel));
+        }
+
+        let name = self.name_of(u)?;
+
+        let c = self
+            .channels
+            .iter_mut()
+            .find(|c| c.name == channel)
+            .ok_or_else(|| fatal("CHANNEL NOT FOUND"))?;
+
+        match a {
+            [] => message(u, format!("Usage: {} <message | history>", channel)),
+            ["history"] => {
+                let history: Vec<&str> = c.history().map(|s| s.as_str()).collect();
+                if history.is_empty() {
+                    message(u, format!("nothing has been said on {} yet", channel))
+                } else {
+                    message(u, history.join("\n"))
+                }
+            }
+            _ if muted => message(u, format!("you have {} muted", channel)),
+            _ if silenced => message(u, "you have been silenced"),
+            _ => {
+                let statement = a.join(" ");
+                let line = format!("[{}] {}: {}", channel, name, statement);
+                c.record(line.clone());
+
+                let role = c.role;
+                let listeners: PlayerIdList = self
+                    .players
+                    .iter()
+                    .filter(|(id, p)| {
+                        let p = p.lock().unwrap();
+                        **id != u
+                            && p.role() >= role
+                            && p.is_subscribed(channel)
+                            && !p.is_muted(channel)
+                    })
+                    .map(|(id, _)| *id)
+                    .collect();
+
+                let msg = Msg {
+                    s: format!("[{}] you: {}", channel, statement).color(Magenta),
+                    o: Some(line.color(Magenta)),
+                };
+
+                message(Audience(u, listeners), msg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_test {
+    use super::*;
+    use crate::game::game_test::game_with_player;
+    use crate::player::Player;
+
+    #[test]
+    fn test_channels() {
+        let (mut g, u) = game_with_player(&[]);
+        g.set_player_name(u, "bill").unwrap();
+
+        let (aud, msg) = g.interpret(u, "gossip hello everyone").unwrap();
+        assert!(msg.to_self().contains("[gossip] you: hello everyone"));
+        assert!(aud.others().is_empty());
+
+        let (_, msg) = g.interpret(u, "gossip history").unwrap();
+        assert!(msg.to_self().contains("[gossip] bill: hello everyone"));
+
+        g.interpret(u, "mute gossip").unwrap();
+        let (_, msg) = g.interpret(u, "chat anyone there?").unwrap();
+        assert!(msg.to_self().contains("you have gossip muted"));
+
+        g.interpret(u, "subscribe newbie").unwrap();
+        let (_, msg) = g.interpret(u, "newbie help").unwrap();
+        assert!(msg.to_self().contains("you aren't subscribed to newbie"));
+
+        let (_, msg) = g.interpret(u, "admin hello").unwrap();
+        assert!(!msg.to_self().contains("[admin]"));
+    }
+
+    #[test]
+    fn test_ignore() {
+        use crate::text::message::{Audience, Broadcast};
+
+        let (mut g, u) = game_with_player(&[]);
+        g.set_player_name(u, "bill").unwrap();
+        let mut p = PlayerType::Human(Player::new());
+        let other = p.uuid();
+        p.set_name("mitchell");
+        g.add_player(p);
+
+        let (_, msg) = g.interpret(other, "ignore bill").unwrap();
+        assert!(msg.to_self().contains("you are now ignoring bill"));
+
+        let (_, msg) = g.interpret(u, "tell mitchell hello?").unwrap();
+        assert!(msg.to_self().contains("mitchell isn't listening to you"));
+
+        let sent = g.send(&Audience(u, vec![other]), &"hello?");
+        assert!(sent.iter().all(|(id, _)| *id != other));
+
+        g.interpret(other, "ignore bill").unwrap();
+        let sent = g.send(&Audience(u, vec![other]), &"hello?");
+        assert!(sent.iter().any(|(id, _)| *id == other));
+    }
+}
@@ -1,10 +1,12 @@
+use super::door::DoorAction;
 use super::item::Direction;
 use super::*;
-use crate::db::recipe_to_item;
+use crate::db::{not_found, recipe_to_item};
 use crate::error::EnnuiError::*;
 use crate::error::{CmdErr, EnnuiError};
 use crate::game::util::random_insult;
-use crate::obstacle::door::{Door, DoorState, Lock, ObstacleState};
+use crate::item::Slot;
+use crate::player::PlayerStatus::{Asleep, Fighting, Sitting, WizInvis};
 use crate::text::message::{Audience, Msg};
 
 use crate::fight::{BasicFight, Fight, FightInfo, FightMod};
@@ -45,7 +47,8 @@ pub fn fill_interpreter(i: &mut Interpreter) {
     });
 
     i.insert("take", |g, u, a| {
-        let a: Vec<_> = a.iter().filter(|&&a| a != "from").collect();
+        let a: Vec<_> = a.iter().filter(|&&a| a != "from").copied().collect();
+        let (n, a) = quantity(&a);
         let name = g.name_of(u)?;
         let loc = g.loc_of(u)?;
         let aud = Audience(u, g.rooms.player_ids(loc).except(u));
@@ -55,15 +58,18 @@ pub fn fill_interpreter(i: &mut Interpreter) {
             0 => "there seems to be an error".to_owned(),
             1 => {
                 let handle = a[0];
-                match g.transfer(u, None, Direction::Take, handle) {
+                match g.transfer_some(u, None, Direction::Take, handle, n) {
                     Ok(handle) => {
-                        other_msg = Some(format!("{} picks up a {}", name, article(&handle)));
+                        other_msg = Some(format!("{} picks up {}", name, article(&handle)));
                         format!("you take the {}", handle)
                     }
                     Err(err) => match err {
                         Simple(CmdErr::TooHeavy) => {
                             format!("you can't pick up {}. It's too heavy", article(handle))
                         }
+                        Simple(CmdErr::NotEnough) => {
+                            format!("there aren't {} of the {} here", n, handle)
+                        }
                         _ => format!("you don't see {} here", article(&handle)),
                     },
                 }
@@ -71,34 +77,49 @@ pub fn fill_interpreter(i: &mut Interpreter) {
             2 => {
                 let player = g.get_player(u)?;
                 let room = g.get_room_mut(loc)?;
-                let (object, container) = (*a[0], *a[1]);
+                let (object, container) = (a[0], a[1]);
 
                 match room.get_item_mut(container.into()) {
                     Some(c) => {
                         if let Item::Container(cont) = c {
                             use std::result::Result::*;
-                            match cont.get_item_mut(object.into()) {
-                                Some(_) => match cont
-                                    .transfer(player.lock().unwrap().deref_mut(), object)
-                                {
-                                    Ok(handle) => {
-                                        other_msg = Some(format!(
-                                            "{} takes {} from {}",
-                                            name,
-                                            article(object),
-                                            article(container),
-                                        ));
-                                        format!("you take the {}", handle)
-                                    }
-                                    Err(_) => {
-                                        "you somehow failed at the simplest of tasks".to_owned()
-                                    }
-                                },
-                                None => format!(
-                                    "you don't see {} in the {}",
-                                    article(object),
-                                    container
-                                ),
+                            let weight = cont
+                                .get_item(object.into())
+                                .map_or(0, |i| Game::weight_of(i, n));
+                            if cont.lid().map_or(false, |l| !l.is_open()) {
+                                format!("the {} is closed", container)
+                            } else if !player.lock().unwrap().can_carry(weight) {
+                                format!("you can't pick up {}. It's too heavy", article(object))
+                            } else {
+                                match cont.get_item_mut(object.into()) {
+                                    Some(_) => match cont.transfer_some(
+                                        player.lock().unwrap().deref_mut(),
+                                        object,
+                                        n,
+                                    ) {
+                                        Ok(handle) => {
+                                            other_msg = Some(format!(
+                                                "{} takes {} from {}",
+                                                name,
+                                                article(object),
+                                                article(container),
+                                            ));
+                                            format!("you take the {}", handle)
+                                        }
+                                        Err(Simple(CmdErr::NotEnough)) => format!(
+                                            "there aren't {} of the {} in the {}",
+                                            n, object, container
+                                        ),
+                                        Err(_) => {
+                                            "you somehow failed at the simplest of tasks".to_owned()
+                                        }
+                                    },
+                                    None => format!(
+                                        "you don't see {} in the {}",
+                                        article(object),
+                                        container
+                                    ),
+                                }
                             }
                         } else {
                             format!("{} is not a container!", article(container))
@@ -119,6 +140,60 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         )
     });
 
+    i.insert("put", |g, u, a| {
+        let a: Vec<_> = a
+            .iter()
+            .filter(|&&a| a != "in" && a != "into")
+            .copied()
+            .collect();
+        let (n, a) = quantity(&a);
+        let name = g.name_of(u)?;
+        let loc = g.loc_of(u)?;
+        let aud = Audience(u, g.rooms.player_ids(loc).except(u));
+
+        let mut other_msg = None;
+        let self_msg = match a.as_slice() {
+            [object, container] => {
+                match g.transfer_some(u, Some(container), Direction::Put, object, n) {
+                    Ok(handle) => {
+                        other_msg = Some(format!(
+                            "{} puts {} in {}",
+                            name,
+                            article(&handle),
+                            article(container)
+                        ));
+                        format!("you put the {} in the {}", handle, container)
+                    }
+                    Err(err) => match err {
+                        Simple(CmdErr::ItemNotFound) => {
+                            format!("you're not holding {}", article(object))
+                        }
+                        Simple(CmdErr::CannotAcceptGivenItem) => {
+                            format!("the {} can't hold {}", container, article(object))
+                        }
+                        Simple(CmdErr::NotEnough) => {
+                            format!("you don't have {} of the {}", n, object)
+                        }
+                        Fatal(e) => {
+                            return Err(Fatal(format!("[{}]: {}", "FATAL".color(Red), e)));
+                        }
+                        Msg(m) => m,
+                        _ => "you somehow failed at the simplest of tasks".to_owned(),
+                    },
+                }
+            }
+            _ => "Usage: put <item> [in] <container>".to_owned(),
+        };
+
+        message(
+            aud,
+            Msg {
+                s: self_msg,
+                o: other_msg,
+            },
+        )
+    });
+
     i.insert("wear", |g, u, a| {
         let name = g.name_of(u)?;
         let loc = g.loc_of(u)?;
@@ -129,11 +204,31 @@ pub fn fill_interpreter(i: &mut Interpreter) {
             0 => "there seems to be an error".to_owned(),
             1 => {
                 let handle = a[0];
+                let replaced = {
+                    let p = g.get_player(u)?;
+                    let p = p.lock().unwrap();
+                    p.get_item(handle.into())
+                        .and_then(|i| i.slot())
+                        .and_then(|s| p.worn(s))
+                        .map(|i| i.name())
+                };
+
                 match g.transfer(u, None, Direction::Wear, handle) {
-                    Ok(item_name) => {
-                        other_msg = Some(format!("{} puts on {}", name, article(&item_name)));
-                        format!("you wear the {}", handle)
-                    }
+                    Ok(item_name) => match replaced {
+                        Some(old) => {
+                            other_msg = Some(format!(
+                                "{} takes off {} and puts on {}",
+                                name,
+                                article(&old),
+                                article(&item_name)
+                            ));
+                            format!("you take off the {} and wear the {}", old, handle)
+                        }
+                        None => {
+                            other_msg = Some(format!("{} puts on {}", name, article(&item_name)));
+                            format!("you wear the {}", handle)
+                        }
+                    },
                     Err(err) => match err {
                         Simple(CmdErr::NotClothing) => {
                             format!("you can't wear {}!", article(handle))
@@ -145,7 +240,7 @@ pub fn fill_interpreter(i: &mut Interpreter) {
                             return Err(Fatal(format!("[{}]: {}", "FATAL".color(Red), e)));
                         }
                         Msg(m) => m,
-                        _ => todo!(),
+                        _ => format!("you can't wear the {} right now", handle),
                     },
                 }
             }
@@ -161,6 +256,50 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         )
     });
 
+    i.insert("wield", |g, u, a| {
+        let name = g.name_of(u)?;
+        let loc = g.loc_of(u)?;
+        let mut other_msg = None;
+
+        let msg = match a {
+            [handle] => {
+                let replaced = g
+                    .get_player(u)?
+                    .lock()
+                    .unwrap()
+                    .worn(Slot::Held)
+                    .map(|i| i.name());
+
+                match g.transfer(u, None, Direction::Wield, handle) {
+                    Ok(item_name) => {
+                        other_msg = Some(format!("{} wields {}", name, article(&item_name)));
+                        match replaced {
+                            Some(old) => {
+                                format!("you put away the {} and wield the {}", old, handle)
+                            }
+                            None => format!("you wield the {}", handle),
+                        }
+                    }
+                    Err(Simple(CmdErr::NotWeapon)) => {
+                        format!("you can't wield {}!", article(handle))
+                    }
+                    Err(Fatal(e)) => return Err(Fatal(e)),
+                    Err(_) => format!("you're not holding {}", article(handle)),
+                }
+            }
+            _ => "be more specific. or less specific.".to_owned(),
+        };
+
+        let others = g.rooms.player_ids(loc).except(u);
+        message(
+            Audience(u, others),
+            Msg {
+                s: msg,
+                o: other_msg,
+            },
+        )
+    });
+
     i.insert("remove", |g, u, a| {
         let name = g.name_of(u)?;
         let loc = g.loc_of(u)?;
@@ -195,13 +334,15 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         let loc = g.loc_of(u)?;
         let mut other_msg = None;
 
+        let (n, a) = quantity(a);
         let msg = if a.len() == 1 {
             let handle = a[0];
-            match g.transfer(u, None, Direction::Drop, handle) {
+            match g.transfer_some(u, None, Direction::Drop, handle, n) {
                 Ok(handle) => {
                     other_msg = Some(format!("{} drops {}", name, article(&handle)));
                     format!("you drop the {}", handle)
                 }
+                Err(Simple(CmdErr::NotEnough)) => format!("you don't have {} of the {}", n, handle),
                 Err(_) => format!("you don't see {} here", article(handle)),
             }
         } else {
@@ -224,10 +365,11 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         let mut other_id = vec![];
         let mut other_msg = None;
 
+        let (n, a) = quantity(a);
         let p_msg = if a.len() == 2 {
             let (handle, other) = (a[0], a[1]);
 
-            match g.transfer(u, Some(other), Direction::Give, handle) {
+            match g.transfer_some(u, Some(other), Direction::Give, handle, n) {
                 Ok(h) => {
                     let art = article(&h);
 
@@ -244,6 +386,7 @@ pub fn fill_interpreter(i: &mut Interpreter) {
                             article(handle)
                         ),
                         CmdErr::PlayerNotFound => "there's no-one by that name here".to_owned(),
+                        CmdErr::NotEnough => format!("you don't have {} of the {}", n, handle),
                         _ => {
                             return Err(fatal("GIVE: SHOULD BE UNREACHABLE"));
                         }
@@ -289,130 +432,89 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         message(aud, msg)
     });
 
-    i.insert("chat", |g, u, a| {
-        let statement = a.join(" ");
-        let name = g.name_of(u)?;
-        let aud = Audience(u, g.players.others());
+    i.insert("whisper", |g, u, a| {
+        if a.len() < 2 {
+            return message(u, "Usage: whisper <player> <message>");
+        }
 
-        let msg = Msg {
-            s: format!("you chat '{}'", statement),
-            o: Some(format!("{} chats '{}'", name, statement)),
+        let loc = g.loc_of(u)?;
+        let other = match g.id_of_in(loc, a[0]) {
+            Some(id) => id,
+            None => return message(u, format!("you don't see {} here", a[0])),
         };
 
-        message(aud, msg)
-    });
-
-    i.insert("evaluate", |g, u, _| {
-        let p = g.players.get(&u)?;
-
-        let mut s = String::new();
-        for meter in p.lock().unwrap().stats() {
-            s.push_str(&format!("{:#?}", meter));
+        if other == u {
+            return message(u, "you mutter something to yourself");
         }
 
-        message(u, s)
-    });
-
-    i.insert("open", |g, u, a| {
-        let loc = g.loc_of(u)?;
+        let statement = a[1..].join(" ");
         let name = g.name_of(u)?;
-        let mut other_msg = None;
-
-        let rooms = &mut g.rooms;
-        let room = rooms.get_mut(&loc)?;
-
-        let self_msg = match a.len() {
-            0 => "ok, what do you want to open?".to_owned(),
-            1 => {
-                if room.doors().len() > 1 {
-                    "which door do you want to open?".to_owned()
-                } else {
-                    let door = match room.doors().iter_mut().next() {
-                        Some((_, d)) => d,
-                        None => return message(u, "there's no door here"),
-                    };
+        let other_name = g.name_of(other)?;
 
-                    try_door_open(&name, &mut other_msg, door)
-                }
-            }
-            2 => {
-                let dir: MapDir = a[1].into();
-                let door = match room.doors().get_mut(&dir) {
-                    Some(d) => d,
-                    None => return message(u, "there's no door in that direction"),
-                };
+        if g.get_player(other)?.lock().unwrap().ignores(&name) {
+            return message(u, format!("{} isn't listening to you", other_name));
+        }
 
-                try_door_open(&name, &mut other_msg, door)
-            }
-            _ => "I'm not sure what you're getting at".to_owned(),
+        let others = g.rooms.player_ids(loc).except(u).except(other);
+        let aud = FightAudience(u, other, others.into_iter().collect());
+        let msg = FightMessage {
+            s: format!("you whisper to {} '{}'", other_name, statement).into(),
+            obj: Some(format!("{} whispers to you '{}'", name, statement).into()),
+            oth: Some(format!("{} whispers something to {}", name, other_name).into()),
         };
 
-        let aud = Audience(u, room.players().except(u));
-        let msg = Msg {
-            s: self_msg,
-            o: other_msg,
-        };
         message(aud, msg)
     });
 
-    i.insert("unlock", |g, u, a| {
-        let loc = g.loc_of(u)?;
-        let name = g.name_of(u)?;
+    i.insert("tell", |g, u, a| {
+        if a.len() < 2 {
+            return message(u, "Usage: tell <player> <message>");
+        }
 
-        let mut other_msg = None;
+        let other = match g.id_of(a[0]) {
+            Some(id) => id,
+            None => return message(u, "there's no-one by that name in the game"),
+        };
 
-        let self_msg = match a.len() {
-            0 => "ok, what do you want to unlock?".to_owned(),
-            1 => {
-                let handle = a[0];
+        tell(g, u, other, &a[1..].join(" "))
+    });
 
-                let player = g.get_player(u)?;
+    i.insert("reply", |g, u, a| {
+        if a.is_empty() {
+            return message(u, "Usage: reply <message>");
+        }
 
-                let room = g.get_room_mut(loc)?;
+        let other = match g.get_player(u)?.lock().unwrap().reply_to() {
+            Some(id) => id,
+            None => return message(u, "nobody has told you anything yet"),
+        };
 
-                let num_doors = room.doors().len();
-                match num_doors {
-                    0 => "there's nothing to unlock here".to_owned(),
-                    1 => {
-                        match handle.to_lowercase().as_str() {
-                            "door" => {
-                                let door = match room.doors().iter_mut().next() {
-                                    Some((_, d)) => d,
-                                    None => return message(u, "there's no door here"),
-                                };
-                                try_door_unlock(name, &mut other_msg, player, door)
-                            }
-                            // TODO: handle other unlockable items (such as chests) here
-                            _ => "I'm not sure that you can even unlock that".to_owned(),
-                        }
-                    }
-                    _ => "that's all greek to me".to_owned(),
-                }
-            }
-            2 => {
-                let player = g.get_player(u)?;
-                let room = g.get_room_mut(loc)?;
+        if !g.players.contains_key(&other) {
+            return message(u, "they're no longer in the game");
+        }
 
-                let dir: MapDir = a[1].into();
-                let door = match room.doors().get_mut(&dir) {
-                    Some(d) => d,
-                    None => return message(u, "there's no door in that direction"),
-                };
+        tell(g, u, other, &a.join(" "))
+    });
 
-                try_door_unlock(name, &mut other_msg, player, door)
-            }
-            _ => "that's pretty much gobbledygook to me".to_owned(),
-        };
+    i.insert("evaluate", |g, u, _| {
+        let p = g.players.get(&u)?;
 
-        let aud = Audience(u, g.rooms.player_ids(loc).except(u));
-        let msg = Msg {
-            s: self_msg,
-            o: other_msg,
-        };
+        let mut s = String::new();
+        for meter in p.lock().unwrap().stats() {
+            s.push_str(&format!("{:#?}", meter));
+        }
 
-        message(aud, msg)
+        message(u, s)
     });
 
+    i.insert("open", |g, u, a| g.door_command(u, a, DoorAction::Open));
+
+    i.insert("close", |g, u, a| g.door_command(u, a, DoorAction::Close));
+
+    i.insert("unlock", |g, u, a| g.door_command(u, a, DoorAction::Unlock));
+
+    i.insert("lock", |g, u, a| g.door_command(u, a, DoorAction::Lock));
+
     i.insert("hit", |g, u, a| {
         let loc = g.loc_of(u)?;
 
@@ -434,8 +536,10 @@ pub fn fill_interpreter(i: &mut Interpreter) {
 
             let sender = g.clone_fight_sender()?;
 
-            let aggressor = g.get_player(u)?;
-            let defender = g.get_player(other_id)?;
+            let mut aggressor = g.get_player(u)?;
+            let mut defender = g.get_player(other_id)?;
+            aggressor.set_attr(Fighting);
+            defender.set_attr(Fighting);
 
             let audience = g.rooms.player_ids(loc).except(u).except(other_id);
             let (mod_sender, receiver) = channel::<FightMod>();
@@ -485,6 +589,7 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         let name = g.name_of(u)?;
         p.unset_attr(Asleep);
         p.set_attr(Sitting);
+        p.lock().unwrap().rest();
 
         let others = g.rooms.player_ids(loc).except(u);
         let aud = Audience(u, others);
@@ -514,6 +619,12 @@ pub fn fill_interpreter(i: &mut Interpreter) {
     i.insert("south", |g, u, _| g.dir_func(u, MapDir::South));
     i.insert("east", |g, u, _| g.dir_func(u, MapDir::East));
     i.insert("west", |g, u, _| g.dir_func(u, MapDir::West));
+    i.insert("northeast", |g, u, _| g.dir_func(u, MapDir::NorthEast));
+    i.insert("northwest", |g, u, _| g.dir_func(u, MapDir::NorthWest));
+    i.insert("southeast", |g, u, _| g.dir_func(u, MapDir::SouthEast));
+    i.insert("southwest", |g, u, _| g.dir_func(u, MapDir::SouthWest));
+    i.insert("up", |g, u, _| g.dir_func(u, MapDir::Up));
+    i.insert("down", |g, u, _| g.dir_func(u, MapDir::Down));
 
     i.insert("inventory", |g, u, _a| {
         let aud = u;
@@ -521,6 +632,22 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         message(aud, msg)
     });
 
+    i.insert("equipment", |g, u, _| {
+        let p = g.get_player(u)?;
+        let p = p.lock().unwrap();
+
+        let mut ret = String::from("EQUIPMENT\n---------");
+        for slot in Slot::ALL.iter() {
+            let worn = match p.worn(*slot) {
+                Some(item) => item.name().color(Green),
+                None => "nothing".to_owned(),
+            };
+            ret.push_str(&format!("\n{:<8}{}", slot, worn));
+        }
+
+        message(u, ret)
+    });
+
     i.insert("who", |g, u, _| {
         let template = "PLAYERS\n-------";
 
@@ -530,7 +657,7 @@ pub fn fill_interpreter(i: &mut Interpreter) {
             .filter_map(|(_, p)| {
                 let conditions = {
                     let q = p.lock().unwrap();
-                    q.uuid() != u && matches!(&*q, PlayerType::Human(_))
+                    q.uuid() != u && !q.is(WizInvis) && matches!(&*q, PlayerType::Human(_))
                 };
 
                 if conditions {
@@ -547,31 +674,18 @@ pub fn fill_interpreter(i: &mut Interpreter) {
         message(u, msg)
     });
 
-    i.insert("help", |_, u, a| {
-        let mut db = match crate::db::DB::new() {
-            Ok(db) => db,
-            Err(e) => {
-                eprintln!("{}", e);
-                print_err(fatal("db problem"));
-                return message(u, "There was an error with the database. Please file an issue at github.com/pmengelbert/ennui, or email peter@engelbert.dev");
-            }
-        };
-
-        let s: String = match a.len() {
-            1 => db.helpfile(a[0]).unwrap_or_else(|e| {
-                eprintln!("{}", e);
-                print_err(fatal("dbproblem"));
-                format!("There was an error with the database. Please file an issue at github.com/pmengelbert/ennui, or email peter@engelbert.dev")
-            }),
-            0 => db.helpfile("commands").unwrap_or_else(|e| {
-                eprintln!("{}", e);
-                print_err(fatal("dbproblem"));
-                format!("There was an error with the database. Please file an issue at github.com/pmengelbert/ennui, or email peter@engelbert.dev")
-            }),
-            _ => "Usage: help <command>".into(),
+    i.insert("help", |g, u, a| {
+        let topic = match a {
+            [] => "commands".to_owned(),
+            [topic] => topic.to_string(),
+            _ => return message(u, "Usage: help <command>"),
         };
 
-        message(u, s)
+        g.in_background(u, move |db, _| match db.helpfile(&topic) {
+            Err(e) if not_found(&*e) => Ok(format!("there's no help on {}", topic)),
+            result => result,
+        })?;
+        no_message()
     });
 
     i.insert("combine", |g, u, a| {
@@ -580,53 +694,44 @@ pub fn fill_interpreter(i: &mut Interpreter) {
             Err(_) => return message(u, "see 'help recipe' for more information"),
         };
 
-        let i = match recipe_to_item(&r) {
-            Ok(i) => i,
-            Err(e) => {
-                print_err(fatal(&format!("{}", e)));
-                return message(u, "that recipe doesn't exist!");
-            }
-        };
-
-        let p = match g.get_player(u) {
-            Ok(p) => p,
-            Err(_) => return Err(fatal("player does not exist")),
-        };
+        let templates = g.templates.clone();
+        g.in_background(u, move |db, p| {
+            let i = match recipe_to_item(db, &r, &templates) {
+                Ok(i) => i,
+                Err(e) if not_found(&*e) => return Ok("that recipe doesn't exist!".into()),
+                Err(e) => return Err(e),
+            };
 
-        let mut p = p.lock().unwrap();
+            let mut p = p.lock().unwrap();
 
-        let name = i.name();
-        let souls = p.souls_mut();
-        if !souls.process_recipe(&r) {
-            return message(u, "you don't have that combination of souls!");
-        };
+            let name = i.name();
+            let souls = p.souls_mut();
+            if !souls.process_recipe(&r) {
+                return Ok("you don't have that combination of souls!".into());
+            };
 
-        p.insert_item(i);
+            p.insert_item(i).ok();
 
-        message(u, format!("you have created {}", article(&name)))
+            Ok(format!("you have created {}", article(&name)))
+        })?;
+        no_message()
     });
 
     i.insert("souls", |g, u, _| {
-        let mut p = match g.get_player(u) {
-            Ok(p) => p,
-            Err(_) => todo!(),
-        };
+        let p = g.get_player(u)?;
 
         let mut ret = String::with_capacity(512);
 
         let p = p.lock().unwrap();
         let list = p.souls().list();
 
-        match list.split_last() {
-            Some((last, list)) => {
-                for s in list {
-                    ret.push_str(&s.name());
-                    ret.push('\n');
-                }
-                ret.push_str(&last.name());
+        if let Some((last, list)) = list.split_last() {
+            for s in list {
+                ret.push_str(&s.name());
+                ret.push('\n');
             }
-            None => (),
-        };
+            ret.push_str(&last.name());
+        }
 
         message(u, ret)
     });
@@ -638,53 +743,119 @@ pub fn fill_interpreter(i: &mut Interpreter) {
     i.insert("quit", |_, _, _| Err(Quit))
 }
 
-fn try_door_unlock(
-    name: String,
-    other_msg: &mut Option<String>,
-    player: Arc<Mutex<PlayerType>>,
-    door: &mut Door,
-) -> String {
-    let mut res = None;
-
-    for item in player.lock().unwrap().list().iter() {
-        if let Item::Key(k) = item {
-            use std::result::Result::*;
-            match door.unlock(DoorState::Closed, Some(k.as_ref())) {
-                Ok(()) => {
-                    *other_msg = Some(format!("{} unlocks a door", name));
-                    res = Some(());
-                    break;
-                }
-                Err(_) => continue,
-            }
-        }
-    }
-    match res {
-        Some(()) => "*click*",
-        None => match door.state() {
-            DoorState::Locked => "you don't have the proper key",
-            DoorState::Closed => "you've already unlocked it",
-            DoorState::Open => "it's already open",
-            DoorState::MagicallySealed => "it's sealed by some unfamiliar magic",
-            DoorState::PermaLocked => "it ain't gonna budge",
-            _ => "wtf",
+/// Splits a leading quantity off the arguments, as in `drop 5 coin`. Without one, it's just the
+/// one item.
+fn quantity<'a>(a: &[&'a str]) -> (u32, Vec<&'a str>) {
+    match a.split_first() {
+        Some((n, rest)) if !rest.is_empty() => match n.parse() {
+            Ok(n) if n > 0 => (n, rest.to_vec()),
+            _ => (1, a.to_vec()),
         },
+        _ => (1, a.to_vec()),
     }
-    .to_owned()
 }
 
-fn try_door_open(name: &str, other_msg: &mut Option<String>, door: &mut Door) -> String {
-    match door.unlock(DoorState::Open, std::option::Option::None) {
-        Ok(_) => {
-            *other_msg = Some(format!("{} opens a door", name));
-            "the door swings open".to_owned()
-        }
-        Err(err) => match err {
-            DoorState::Locked => "that door is locked".to_owned(),
-            DoorState::Open => "it's already open".to_owned(),
-            DoorState::MagicallySealed => "it's sealed by some unfamiliar magic".to_owned(),
-            DoorState::PermaLocked => "it ain't gonna budge".to_owned(),
-            _ => "wtf".to_owned(),
-        },
+fn tell(g: &mut Game, u: u128, other: u128, statement: &str) -> Result<GameOutput, EnnuiError> {
+    if other == u {
+        return message(u, "you mutter something to yourself");
+    }
+
+    let name = g.name_of(u)?;
+    let other_p = g.get_player(other)?;
+    let mut other_p = other_p.lock().unwrap();
+    let other_name = other_p.name();
+
+    if let PlayerType::Npc(_) = &*other_p {
+        return message(u, format!("{} doesn't seem to hear you", other_name));
+    }
+
+    if other_p.ignores(&name) {
+        return message(u, format!("{} isn't listening to you", other_name));
+    }
+
+    other_p.set_reply_to(u);
+
+    let aud = FightAudience(u, other, vec![]);
+    let msg = FightMessage {
+        s: format!("you tell {} '{}'", other_name, statement).into(),
+        obj: Some(format!("{} tells you '{}'", name, statement).into()),
+        oth: None,
+    };
+
+    message(aud, msg)
+}
+
+#[cfg(test)]
+mod commands_test {
+    use super::*;
+    use crate::game::game_test::game_with_player;
+    use crate::player::Player;
+
+    #[test]
+    fn test_private_messages() {
+        let (mut g, u) = game_with_player(&[]);
+        g.set_player_name(u, "bill").unwrap();
+        let mut p = PlayerType::Human(Player::new());
+        let other = p.uuid();
+        p.set_name("mitchell");
+        g.add_player(p);
+
+        let (_, msg) = g.interpret(u, "whisper mitchell hello there").unwrap();
+        let s = msg.to_self();
+        assert!(s.contains("you whisper to mitchell 'hello there'"));
+        assert!(msg.to_object().unwrap().contains("bill whispers to you"));
+        let others = msg.to_others().unwrap();
+        assert!(others.contains("whispers something to mitchell"));
+
+        let (_, msg) = g.interpret(other, "reply hi").unwrap();
+        assert!(msg.to_self().contains("nobody has told you anything yet"));
+
+        g.interpret(u, "tell mitchell psst").unwrap();
+        let (_, msg) = g.interpret(other, "reply what").unwrap();
+        assert!(msg.to_self().contains("you tell bill 'what'"));
+
+        let (_, msg) = g.interpret(u, "tell nobody hello").unwrap();
+        assert!(msg.to_self().contains("there's no-one by that name"));
+    }
+
+    #[test]
+    fn test_combine_in_background() {
+        use crate::db::sqlite::sqlite_test::seeded_file;
+        use crate::db::Pool;
+        use crate::soul::SoulKind;
+        use std::time::Duration;
+
+        let (mut g, u) = game_with_player(&[]);
+        g.set_db(Pool::new(
+            &seeded_file("combine"),
+            1,
+            Duration::from_secs(5),
+        ));
+        let p = g.players_mut().get(&u).unwrap().clone();
+        let explorers = |p: &Arc<Mutex<PlayerType>>| {
+            let p = p.lock().unwrap();
+            let souls = p.souls().list();
+            souls
+                .iter()
+                .filter(|s| matches!(s, SoulKind::Exploration))
+                .count()
+        };
+        p.lock()
+            .unwrap()
+            .souls_mut()
+            .insert_item(SoulKind::Exploration)
+            .unwrap();
+        let before = explorers(&p);
+
+        g.interpret(u, "combine 0.crafting 1.exploration 0.combat")
+            .unwrap();
+
+        let made = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(50));
+            let (_, msg) = g.interpret(u, "inventory").unwrap();
+            msg.to_self().contains("hat")
+        });
+        assert!(made);
+        assert_eq!(explorers(&p), before - 1);
     }
 }
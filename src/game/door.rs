@@ -0,0 +1,377 @@
+use super::*;
+use crate::obstacle::door::Lock;
+
+/// `DoorAction` is what a player is trying to do to a door
+#[derive(Clone, Copy)]
+pub enum DoorAction {
+    Open,
+    Close,
+    Unlock,
+    Lock,
+}
+
+impl DoorAction {
+    fn verb(&self) -> &'static str {
+        match self {
+            DoorAction::Open => "open",
+            DoorAction::Close => "close",
+            DoorAction::Unlock => "unlock",
+            DoorAction::Lock => "lock",
+        }
+    }
+
+    /// What players on the far side of the door notice
+    fn heard(&self) -> &'static str {
+        match self {
+            DoorAction::Open => "swings open",
+            DoorAction::Close => "swings shut",
+            DoorAction::Unlock | DoorAction::Lock => "clicks",
+        }
+    }
+}
+
+impl Game {
+    /// Opens, closes, unlocks or locks one of the doors in the player's room, keeping the door
+    /// on the far side in step. A container in the room or in the player's hands can be named
+    /// instead of a door.
+    pub(super) fn door_command(
+        &mut self,
+        u: u128,
+        a: &[&str],
+        action: DoorAction,
+    ) -> Result<GameOutput, EnnuiError> {
+        let loc = self.loc_of(u)?;
+        let name = self.name_of(u)?;
+        let player = self.get_player(u)?;
+
+        if let [handle] = a {
+            if MapDir::from(*handle) == MapDir::NoneFound
+                && (self.get_room(loc)?.get_item((*handle).into()).is_some()
+                    || player.lock().unwrap().get_item((*handle).into()).is_some())
+            {
+                return self.lid_command(u, handle, action);
+            }
+        }
+
+        let dir = match door_dir(self.get_room(loc)?, a, action.verb()) {
+            Ok(dir) => dir,
+            Err(msg) => return message(u, msg),
+        };
+
+        let mut other_msg = None;
+        let door = self
+            .get_room_mut(loc)?
+            .doors()
+            .get_mut(&dir)
+            .ok_or_else(|| fatal("UNABLE TO FIND DOOR"))?;
+
+        let self_msg = match action {
+            DoorAction::Open => try_door_open(&name, &mut other_msg, door, "door"),
+            DoorAction::Close => try_door_close(&name, &mut other_msg, door, "door"),
+            DoorAction::Unlock => try_door_unlock(name, &mut other_msg, player, door, "door"),
+            DoorAction::Lock => try_door_lock(name, &mut other_msg, player, door, "door"),
+        };
+
+        if other_msg.is_some() {
+            self.sync_door(loc, dir, action)?;
+        }
+
+        let aud = Audience(u, self.rooms.player_ids(loc).except(u));
+        let msg = Msg {
+            s: self_msg,
+            o: other_msg,
+        };
+
+        message(aud, msg)
+    }
+
+    /// Opens, closes, unlocks or locks the lid of a container in the player's room or hands
+    fn lid_command(
+        &mut self,
+        u: u128,
+        handle: &str,
+        action: DoorAction,
+    ) -> Result<GameOutput, EnnuiError> {
+        let loc = self.loc_of(u)?;
+        let name = self.name_of(u)?;
+        let player = self.get_player(u)?;
+
+        // a carried container is taken out of the player's hands while its lid is worked, so that
+        // their keys can be looked through, and handed back afterwards
+        let mut held = match self.get_room(loc)?.get_item(handle.into()) {
+            Some(_) => None,
+            None => Some(player.lock().unwrap().get_item_owned(handle.into())?),
+        };
+        let item = match held.as_mut() {
+            Some(item) => item,
+            None => self
+                .get_room_mut(loc)?
+                .get_item_mut(handle.into())
+                .ok_or_else(|| fatal("UNABLE TO FIND ITEM"))?,
+        };
+        let noun = item.name();
+
+        let lid = match item {
+            Item::Container(c) => c.lid_mut(),
+            _ => None,
+        };
+
+        let mut other_msg = None;
+        let self_msg = match (lid, action) {
+            (None, _) => format!("you can't {} {}", action.verb(), article(&noun)),
+            (Some(lid), DoorAction::Open) => try_door_open(&name, &mut other_msg, lid, &noun),
+            (Some(lid), DoorAction::Close) => try_door_close(&name, &mut other_msg, lid, &noun),
+            (Some(lid), DoorAction::Unlock) => {
+                try_door_unlock(name, &mut other_msg, player.clone(), lid, &noun)
+            }
+            (Some(lid), DoorAction::Lock) => {
+                try_door_lock(name, &mut other_msg, player.clone(), lid, &noun)
+            }
+        };
+
+        if let Some(item) = held {
+            player
+                .lock()
+                .unwrap()
+                .insert_item(item)
+                .map_err(|_| fatal("UNABLE TO RETURN ITEM"))?;
+        }
+
+        let aud = Audience(u, self.rooms.player_ids(loc).except(u));
+        let msg = Msg {
+            s: self_msg,
+            o: other_msg,
+        };
+
+        message(aud, msg)
+    }
+
+    /// Copies the state of a door to its twin in the neighbouring room, and lets anyone there
+    /// know that something happened
+    fn sync_door(&mut self, loc: Coord, dir: MapDir, action: DoorAction) -> Result<(), EnnuiError> {
+        let state = self
+            .get_room(loc)?
+            .doors()
+            .get(&dir)
+            .ok_or_else(|| fatal("UNABLE TO FIND DOOR"))?
+            .state();
+
+        let far = match self.rooms.dest(loc, dir) {
+            Some(c) if self.rooms.contains_key(&c) => c,
+            _ => return Ok(()),
+        };
+
+        let msg = if self.rooms.off_grid(loc, dir) {
+            format!("somewhere nearby, a door {}", action.heard())
+        } else {
+            let back = dir.opposite();
+            if let Some(twin) = self.get_room_mut(far)?.doors().get_mut(&back) {
+                twin.set_state(state);
+            }
+            format!("the door to the {} {}", back.to_string(), action.heard())
+        };
+
+        let aud = self.rooms.player_ids(far);
+        self.send(&aud, &msg.padded());
+
+        Ok(())
+    }
+}
+
+/// Works out which door a player means. `open door` will do when there's only one door in the
+/// room; otherwise the player has to give a direction, as in `open north` or `open door north`.
+fn door_dir(room: &Room, a: &[&str], verb: &str) -> Result<MapDir, String> {
+    let doors = room.doors();
+
+    let dir = match a {
+        [] => return Err(format!("ok, what do you want to {}?", verb)),
+        [word] => match MapDir::from(*word) {
+            MapDir::NoneFound => match doors.len() {
+                0 => return Err("there's no door here".to_owned()),
+                1 => {
+                    return doors
+                        .keys()
+                        .next()
+                        .copied()
+                        .ok_or_else(|| "there's no door here".to_owned())
+                }
+                _ => return Err(format!("which door do you want to {}?", verb)),
+            },
+            dir => dir,
+        },
+        [_, dir] => MapDir::from(*dir),
+        _ => return Err("I'm not sure what you're getting at".to_owned()),
+    };
+
+    if doors.contains_key(&dir) {
+        Ok(dir)
+    } else {
+        Err("there's no door in that direction".to_owned())
+    }
+}
+
+fn try_door_unlock<L: Lock<DoorState, Lock = u64>>(
+    name: String,
+    other_msg: &mut Option<String>,
+    player: Arc<Mutex<PlayerType>>,
+    door: &mut L,
+    noun: &str,
+) -> String {
+    let mut res = None;
+
+    for item in player.lock().unwrap().list().iter() {
+        if let Item::Key(k) = item {
+            use std::result::Result::*;
+            match door.unlock(DoorState::Closed, Some(k.as_ref())) {
+                Ok(()) => {
+                    *other_msg = Some(format!("{} unlocks {}", name, article(noun)));
+                    res = Some(());
+                    break;
+                }
+                Err(_) => continue,
+            }
+        }
+    }
+    match res {
+        Some(()) => "*click*",
+        None => match door.state() {
+            DoorState::Locked => "you don't have the proper key",
+            DoorState::Closed => "you've already unlocked it",
+            DoorState::Open => "it's already open",
+            DoorState::MagicallySealed => "it's sealed by some unfamiliar magic",
+            DoorState::PermaLocked => "it ain't gonna budge",
+            _ => "wtf",
+        },
+    }
+    .to_owned()
+}
+
+fn try_door_open<L: Lock<DoorState, Lock = u64>>(
+    name: &str,
+    other_msg: &mut Option<String>,
+    door: &mut L,
+    noun: &str,
+) -> String {
+    match door.unlock(DoorState::Open, std::option::Option::None) {
+        Ok(_) => {
+            *other_msg = Some(format!("{} opens {}", name, article(noun)));
+            format!("the {} swings open", noun)
+        }
+        Err(err) => match err {
+            DoorState::Locked => format!("that {} is locked", noun),
+            DoorState::Open => "it's already open".to_owned(),
+            DoorState::MagicallySealed => "it's sealed by some unfamiliar magic".to_owned(),
+            DoorState::PermaLocked => "it ain't gonna budge".to_owned(),
+            _ => "wtf".to_owned(),
+        },
+    }
+}
+
+fn try_door_close<L: Lock<DoorState, Lock = u64>>(
+    name: &str,
+    other_msg: &mut Option<String>,
+    door: &mut L,
+    noun: &str,
+) -> String {
+    match door.lock(DoorState::Closed, std::option::Option::None) {
+        Ok(_) => {
+            *other_msg = Some(format!("{} closes {}", name, article(noun)));
+            format!("the {} swings shut", noun)
+        }
+        Err(err) => match err {
+            DoorState::Open => "wtf".to_owned(),
+            DoorState::MagicallySealed => "it's sealed by some unfamiliar magic".to_owned(),
+            DoorState::PermaLocked => "it ain't gonna budge".to_owned(),
+            _ => "it's already closed".to_owned(),
+        },
+    }
+}
+
+fn try_door_lock<L: Lock<DoorState, Lock = u64>>(
+    name: String,
+    other_msg: &mut Option<String>,
+    player: Arc<Mutex<PlayerType>>,
+    door: &mut L,
+    noun: &str,
+) -> String {
+    if door.state() == DoorState::Closed && door.keyhole().is_none() {
+        return "there's no keyhole".to_owned();
+    }
+
+    for item in player.lock().unwrap().list().iter() {
+        if let Item::Key(k) = item {
+            if door.lock(DoorState::Locked, Some(k.as_ref())).is_ok() {
+                *other_msg = Some(format!("{} locks {}", name, article(noun)));
+                return "*click*".to_owned();
+            }
+        }
+    }
+
+    match door.state() {
+        DoorState::Open => "you'll have to close it first",
+        DoorState::Closed => "you don't have the proper key",
+        DoorState::Locked => "it's already locked",
+        DoorState::MagicallySealed => "it's sealed by some unfamiliar magic",
+        DoorState::PermaLocked => "it ain't gonna budge",
+        _ => "wtf",
+    }
+    .to_owned()
+}
+
+#[cfg(test)]
+mod door_test {
+    use super::*;
+    use crate::game::game_test::{game_with_items, game_with_player};
+
+    #[test]
+    fn test_doors() {
+        let (mut g, u) = game_with_player(&[]);
+
+        let (_, msg) = g.interpret(u, "close").unwrap();
+        assert!(msg.to_self().contains("what do you want to close?"));
+
+        let (_, msg) = g.interpret(u, "lock north").unwrap();
+        assert!(msg.to_self().contains("it's already locked"));
+
+        let (_, msg) = g.interpret(u, "close west").unwrap();
+        assert!(msg.to_self().contains("there's no door in that direction"));
+    }
+
+    #[test]
+    fn test_carried_lids() {
+        let x = r#"---
+inner:
+- Container:
+    info:
+      name: box
+      display: A little box sits here
+      description: It's a box.
+      handle: [box]
+    lid:
+      state: Locked
+      keyhole: 4
+    inner: []
+- Key:
+  - 4
+  - name: key
+    display: A tiny key lies here
+    description: It's a key.
+    handle: [key]
+"#;
+        let (mut g, u) = game_with_items(x);
+        let p = g.players_mut().get(&u).unwrap().clone();
+
+        let (_, msg) = g.interpret(u, "open box").unwrap();
+        assert!(msg.to_self().contains("that box is locked"));
+
+        let (_, msg) = g.interpret(u, "unlock box").unwrap();
+        assert!(msg.to_self().contains("*click*"));
+
+        let (_, msg) = g.interpret(u, "open box").unwrap();
+        assert!(msg.to_self().contains("the box swings open"));
+
+        let (_, msg) = g.interpret(u, "put key in box").unwrap();
+        assert!(msg.to_self().contains("you put the key in the box"));
+        assert!(p.lock().unwrap().get_item("box".into()).is_some());
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::attribute::Quality;
+
+pub fn fill_interpreter(i: &mut Interpreter) {
+    i.insert("eat", |g, u, a| g.consume(u, a, false));
+    i.insert("drink", |g, u, a| g.consume(u, a, true));
+}
+
+impl Game {
+    /// Eats or drinks something from the player's inventory
+    fn consume(&mut self, u: u128, a: &[&str], drink: bool) -> Result<GameOutput, EnnuiError> {
+        let (verb, verbs, instead) = if drink {
+            ("drink", "drinks", "eating")
+        } else {
+            ("eat", "eats", "drinking")
+        };
+
+        let handle = match a {
+            [handle] => *handle,
+            _ => return message(u, format!("what do you want to {}?", verb)),
+        };
+
+        let name = self.name_of(u)?;
+        let loc = self.loc_of(u)?;
+        let p = self.get_player(u)?;
+        let mut p = p.lock().unwrap();
+
+        let food = match p.get_item(handle.into()) {
+            None => return message(u, format!("you're not holding {}", article(handle))),
+            Some(i) if !i.is(Quality::Edible) => {
+                return message(u, format!("you can't {} {}!", verb, article(handle)))
+            }
+            Some(i) => i.food().cloned().unwrap_or_default(),
+        };
+
+        if food.drink != drink {
+            let msg = format!("you can't {} {}. try {} it", verb, article(handle), instead);
+            return message(u, msg);
+        }
+
+        let item_name = p.get_some_owned(handle.into(), 1)?.name();
+        let mut felt = vec![format!("you {} the {}", verb, item_name)];
+        felt.extend(p.consume(&food));
+
+        let others = self.rooms.player_ids(loc).except(u);
+        let msg = Msg {
+            s: felt.join("\n"),
+            o: Some(format!("{} {} {}", name, verbs, article(&item_name))),
+        };
+
+        message(Audience(u, others), msg)
+    }
+}
+
+#[cfg(test)]
+mod food_test {
+    use crate::game::game_test::game_with_items;
+
+    #[test]
+    fn test_eat_and_drink() {
+        let x = r#"---
+inner:
+- Edible:
+    name: apple
+    display: An apple lies here
+    description: It's an apple.
+    handle: [apple]
+    food:
+      fills: 10
+      effects:
+      - Buff: {stat: Strength, amount: 2, ticks: 3}
+- Edible:
+    name: flask
+    display: A flask lies here
+    description: It's a flask of water.
+    handle: [flask]
+    food:
+      drink: true
+- Holdable:
+    name: stick
+    display: A stick lies here
+    description: It's a stick.
+    handle: [stick]
+"#;
+        let (mut g, u) = game_with_items(x);
+
+        let (_, msg) = g.interpret(u, "eat stick").unwrap();
+        assert!(msg.to_self().contains("you can't eat a stick"));
+
+        let (_, msg) = g.interpret(u, "eat flask").unwrap();
+        assert!(msg.to_self().contains("try drinking it"));
+
+        let (_, msg) = g.interpret(u, "eat apple").unwrap();
+        assert!(msg.to_self().contains("you eat the apple"));
+        assert!(msg.to_self().contains("you feel your strength grow"));
+
+        let (_, msg) = g.interpret(u, "drink flask").unwrap();
+        assert!(msg.to_self().contains("you drink the flask"));
+
+        let (_, msg) = g.interpret(u, "inventory").unwrap();
+        assert!(!msg.to_self().contains("apple"));
+        assert!(!msg.to_self().contains("flask"));
+    }
+}
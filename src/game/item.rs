@@ -1,7 +1,6 @@
 use crate::game::{fatal, Game};
 
 use crate::attribute::{Attribute, Quality};
-use crate::describe::Describe;
 use crate::item::Item;
 use crate::location::Coord;
 use crate::map::list::RoomList;
@@ -9,10 +8,10 @@ use crate::player::list::PlayerList;
 use crate::player::{PlayerType, Uuid};
 use crate::text::article;
 
-use crate::error::CmdErr::{ItemNotFound, NotClothing, PlayerNotFound, TooHeavy};
+use crate::error::CmdErr::{ItemNotFound, NotClothing, NotWeapon, PlayerNotFound, TooHeavy};
 use crate::error::EnnuiError;
 use crate::error::EnnuiError::{Fatal, Msg, Simple};
-use crate::list::{List, ListTrait};
+use crate::list::{List, ListTrait, Stack};
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
@@ -21,11 +20,20 @@ pub enum Direction {
     Take,
     Give,
     Drop,
+    Put,
     Wear,
+    Wield,
     Remove,
 }
 
 impl Game {
+    /// Puts an item down in the given room
+    pub fn place_item(&mut self, loc: Coord, item: Item) -> Result<(), EnnuiError> {
+        self.get_room_mut(loc)?
+            .insert_item(item)
+            .map_err(|_| fatal("UNABLE TO PLACE ITEM"))
+    }
+
     pub fn transfer<T>(
         &mut self,
         u: T,
@@ -33,6 +41,22 @@ impl Game {
         dir: Direction,
         handle: &str,
     ) -> Result<String, EnnuiError>
+    where
+        T: Uuid,
+    {
+        self.transfer_some(u, other, dir, handle, 1)
+    }
+
+    /// Moves `n` of an item, splitting it off a stack if need be. Anything that can't be
+    /// split, like wearing clothes, moves just the one.
+    pub fn transfer_some<T>(
+        &mut self,
+        u: T,
+        other: Option<&str>,
+        dir: Direction,
+        handle: &str,
+        n: u32,
+    ) -> Result<String, EnnuiError>
     where
         T: Uuid,
     {
@@ -54,10 +78,20 @@ impl Game {
         let players = &mut self.players;
 
         match dir {
-            Take => Self::take(rooms, players, uuid, loc, handle),
-            Drop => Self::drop(rooms, players, uuid, loc, handle),
-            Give => Self::give(players, rooms, loc, (uuid, other_id), other, handle),
+            Take => Self::take(rooms, players, uuid, loc, handle, n),
+            Drop => Self::drop(rooms, players, uuid, loc, handle, n),
+            Give => Self::give(players, rooms, loc, (uuid, other_id), other, handle, n),
+            Put => Self::put(
+                rooms,
+                players,
+                uuid,
+                loc,
+                other.unwrap_or_default(),
+                handle,
+                n,
+            ),
             Wear => Self::wear(players, uuid, handle),
+            Wield => Self::wield(players, uuid, handle),
             Remove => Self::remove(players, uuid, handle),
         }
     }
@@ -68,16 +102,18 @@ impl Game {
         uuid: u128,
         loc: &Coord,
         handle: &str,
+        n: u32,
     ) -> Result<String, EnnuiError> {
         let room = match rooms.get_mut(loc) {
             Some(r) => r,
             None => return Err(Simple(ItemNotFound)),
         };
 
-        match room.get_item(handle.into()) {
+        let weight = match room.get_item(handle.into()) {
             Some(s) if s.is(Quality::Scenery) => return Err(Simple(TooHeavy)),
-            _ => (),
-        }
+            Some(s) => Self::weight_of(s, n),
+            None => 0,
+        };
 
         let player = players.get_mut(&uuid).ok_or_else(|| {
             fatal(&format!(
@@ -85,7 +121,17 @@ impl Game {
                 uuid
             ))
         })?;
-        room.transfer(player.lock().unwrap().deref_mut(), handle)
+        let mut player = player.lock().unwrap();
+        if !player.can_carry(weight) {
+            return Err(Simple(TooHeavy));
+        }
+
+        room.transfer_some(player.deref_mut(), handle, n)
+    }
+
+    /// What `n` of the item weigh, when it's a stack
+    pub fn weight_of(item: &Item, n: u32) -> u32 {
+        item.weight() / item.count() * n.min(item.count())
     }
 
     fn drop(
@@ -94,6 +140,7 @@ impl Game {
         uuid: u128,
         loc: &Coord,
         handle: &str,
+        n: u32,
     ) -> Result<String, EnnuiError> {
         let room = rooms.get_mut(loc).ok_or_else(|| {
             fatal(&format!(
@@ -104,7 +151,7 @@ impl Game {
         let player = players
             .get_mut(&uuid)
             .ok_or_else(|| fatal(&format!("unable to find player {}", uuid)))?;
-        player.lock().unwrap().transfer(room, handle)
+        player.lock().unwrap().transfer_some(room, handle, n)
     }
 
     fn give(
@@ -114,6 +161,7 @@ impl Game {
         ids: (u128, u128),
         other_name: Option<&str>,
         handle: &str,
+        n: u32,
     ) -> Result<String, EnnuiError> {
         let (uuid, other_id) = ids;
         let item = {
@@ -121,10 +169,10 @@ impl Game {
                 .get_mut(&uuid)
                 .ok_or_else(|| Fatal(format!("unable to find player {}", uuid)))?;
 
-            p.lock().unwrap().get_item_owned(handle.into())?
+            p.lock().unwrap().get_some_owned(handle.into(), n)?
         };
 
-        let item_name = item.name();
+        let item_name = item.label();
 
         let other_p = match players.get_mut(&other_id) {
             Some(p) => p,
@@ -192,6 +240,18 @@ impl Game {
             }
         };
 
+        if !other_p.lock().unwrap().can_carry(item.weight()) {
+            players
+                .get_mut(&uuid)
+                .ok_or_else(|| fatal("wasn't able to find the original player ..."))?
+                .lock()
+                .unwrap()
+                .insert_item(item)
+                .map_err(|_| fatal("wasn't able to return item to player after failed give"))?;
+
+            return Err(Simple(TooHeavy));
+        }
+
         if other_p.lock().unwrap().insert_item(item).is_err() {
             return Err(Fatal(format!(
                 "COULD NOT RETURN ITEM {} TO OTHER PLAYER {}",
@@ -202,11 +262,110 @@ impl Game {
         Ok(item_name)
     }
 
+    /// Puts an item from the player's inventory into a container, either one they're carrying
+    /// or one in the room
+    fn put(
+        rooms: &mut RoomList,
+        players: &mut PlayerList,
+        uuid: u128,
+        loc: &Coord,
+        container: &str,
+        handle: &str,
+        n: u32,
+    ) -> Result<String, EnnuiError> {
+        let p = Self::get_player_mut(players, uuid)?;
+        let mut p = p.lock().unwrap();
+
+        let (same, carried) = {
+            let item = p.get_item(handle.into()).ok_or(Simple(ItemNotFound))?;
+            match p.get_item(container.into()) {
+                Some(cont) => (std::ptr::eq(item, cont), true),
+                None => (false, false),
+            }
+        };
+
+        if same {
+            return Err(Msg(format!(
+                "you can't put {} into itself",
+                article(container)
+            )));
+        }
+
+        if carried {
+            let mut cont = p.get_item_owned(container.into())?;
+            let res = Self::put_into(p.deref_mut(), &mut cont, container, handle, n);
+            p.insert_item(cont)
+                .map_err(|_| fatal("unable to return container to player"))?;
+            return res;
+        }
+
+        let room = rooms.get_mut(loc).ok_or_else(|| {
+            fatal(&format!(
+                "unable to find room for player {} at {:?}",
+                uuid, loc
+            ))
+        })?;
+        match room.get_item_mut(container.into()) {
+            Some(cont) => Self::put_into(p.deref_mut(), cont, container, handle, n),
+            None => Err(Msg(format!("you don't see {} here", article(container)))),
+        }
+    }
+
+    fn put_into(
+        from: &mut dyn ListTrait<Item = Item>,
+        cont: &mut Item,
+        container: &str,
+        handle: &str,
+        n: u32,
+    ) -> Result<String, EnnuiError> {
+        let cont = match cont {
+            Item::Container(c) => c,
+            _ => return Err(Msg(format!("{} is not a container!", article(container)))),
+        };
+
+        if cont.lid().map_or(false, |l| !l.is_open()) {
+            return Err(Msg(format!("the {} is closed", container)));
+        }
+        if cont.is_full() {
+            return Err(Msg(format!("the {} is full", container)));
+        }
+
+        from.transfer_some(cont.as_list_mut(), handle, n)
+    }
+
     fn wear(players: &mut PlayerList, uuid: u128, handle: &str) -> Result<String, EnnuiError> {
         let p = Self::get_player_mut(players, uuid)?;
         let mut p = p.lock().unwrap();
         let (items, clothing) = p.all_items_mut();
         Self::check_if_clothing(handle, items)?;
+        Self::put_on(items, clothing, handle)
+    }
+
+    fn wield(players: &mut PlayerList, uuid: u128, handle: &str) -> Result<String, EnnuiError> {
+        let p = Self::get_player_mut(players, uuid)?;
+        let mut p = p.lock().unwrap();
+        let (items, clothing) = p.all_items_mut();
+        match items.get_item(handle.into()) {
+            Some(i) if i.is(Quality::Weapon) => (),
+            None => return Err(Simple(ItemNotFound)),
+            _ => return Err(Simple(NotWeapon)),
+        }
+        Self::put_on(items, clothing, handle)
+    }
+
+    /// Moves an item into its slot, taking off whatever was there before
+    fn put_on(
+        items: &mut List<Item, Quality>,
+        clothing: &mut List<Item, Quality>,
+        handle: &str,
+    ) -> Result<String, EnnuiError> {
+        let slot = items.get_item(handle.into()).and_then(|i| i.slot());
+        if let Some(worn) = clothing.take_where(|i| slot.is_some() && i.slot() == slot) {
+            items
+                .insert_item(worn)
+                .map_err(|_| fatal("unable to return clothing to player"))?;
+        }
+
         items.transfer(clothing, handle)
     }
 
@@ -278,3 +437,218 @@ impl Game {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod item_test {
+    use super::*;
+    use crate::game::game_test::{game_with_items, items};
+    use crate::player::Player;
+
+    #[test]
+    fn test_put() {
+        let x = r#"---
+inner:
+- Container:
+    info:
+      name: bag
+      display: A small bag lies here
+      description: It's a bag.
+      handle: [bag]
+    capacity: 1
+    inner: []
+- Holdable:
+    name: coin
+    display: A coin glints here
+    description: It's a coin.
+    handle: [coin]
+- Holdable:
+    name: ring
+    display: A ring glints here
+    description: It's a ring.
+    handle: [ring]
+"#;
+        let (mut g, u) = game_with_items(x);
+
+        let (_, msg) = g.interpret(u, "put bag in bag").unwrap();
+        assert!(msg.to_self().contains("into itself"));
+
+        let (_, msg) = g.interpret(u, "put coin in bag").unwrap();
+        assert!(msg.to_self().contains("you put the coin in the bag"));
+
+        let (_, msg) = g.interpret(u, "put ring in bag").unwrap();
+        assert!(msg.to_self().contains("the bag is full"));
+
+        let (_, msg) = g.interpret(u, "put ring in corpse").unwrap();
+        assert!(msg.to_self().contains("you put the ring in the corpse"));
+
+        let (_, msg) = g.interpret(u, "put ring in corpse").unwrap();
+        assert!(msg.to_self().contains("you're not holding a ring"));
+    }
+
+    #[test]
+    fn test_weight() {
+        let x = r#"---
+inner:
+- Holdable:
+    name: anvil
+    display: An anvil sits here
+    description: It's an anvil.
+    handle: [anvil]
+    weight: 60
+- Holdable:
+    name: boulder
+    display: A boulder sits here
+    description: It's a boulder.
+    handle: [boulder]
+    weight: 50
+"#;
+        let (mut g, u) = game_with_items(x);
+
+        let (_, msg) = g.interpret(u, "inventory").unwrap();
+        let msg = msg.to_self();
+        assert!(msg.contains("you are carrying 110 of a possible 100"));
+        assert!(msg.contains("weighed down"));
+
+        g.interpret(u, "drop boulder").unwrap();
+        let (_, msg) = g.interpret(u, "take boulder").unwrap();
+        assert!(msg.to_self().contains("It's too heavy"));
+
+        g.interpret(u, "drop anvil").unwrap();
+        let (_, msg) = g.interpret(u, "take boulder").unwrap();
+        assert!(msg.to_self().contains("you take the boulder"));
+    }
+
+    #[test]
+    fn test_equipment_slots() {
+        let x = r#"---
+inner:
+- Clothing:
+    name: hat
+    display: A hat lies here
+    description: It's a hat.
+    handle: [hat]
+    slot: Head
+- Clothing:
+    name: cap
+    display: A cap lies here
+    description: It's a cap.
+    handle: [cap]
+    slot: Head
+- Clothing:
+    name: cloak
+    display: A cloak lies here
+    description: It's a cloak.
+    handle: [cloak]
+"#;
+        let (mut g, u) = game_with_items(x);
+
+        let (_, msg) = g.interpret(u, "wear hat").unwrap();
+        assert!(msg.to_self().contains("you wear the hat"));
+
+        let (_, msg) = g.interpret(u, "wear cap").unwrap();
+        let msg = msg.to_self();
+        assert!(msg.contains("you take off the hat and wear the cap"));
+
+        g.interpret(u, "wear cloak").unwrap();
+        let (_, msg) = g.interpret(u, "equipment").unwrap();
+        let msg = msg.to_self();
+        assert!(msg.contains("cap"));
+        assert!(msg.contains("cloak"));
+        assert!(!msg.contains("hat"));
+        assert!(msg.contains("nothing"));
+    }
+
+    #[test]
+    fn test_wield() {
+        let x = r#"---
+inner:
+- Weapon:
+    name: sword
+    display: A sword lies here
+    description: It's a sword.
+    handle: [sword]
+    weapon:
+      damage: 1d8
+      kind: Slashing
+- Holdable:
+    name: stick
+    display: A stick lies here
+    description: It's a stick.
+    handle: [stick]
+"#;
+        let (mut g, u) = game_with_items(x);
+        assert!(g.get_player(u).unwrap().lock().unwrap().wielded().is_none());
+
+        let (_, msg) = g.interpret(u, "wield stick").unwrap();
+        assert!(msg.to_self().contains("you can't wield a stick"));
+
+        let (_, msg) = g.interpret(u, "wield sword").unwrap();
+        assert!(msg.to_self().contains("you wield the sword"));
+
+        let (_, msg) = g.interpret(u, "equipment").unwrap();
+        assert!(msg.to_self().contains("sword"));
+    }
+
+    #[test]
+    fn test_stacks() {
+        let x = r#"---
+inner:
+- Holdable:
+    name: coin
+    display: A pile of coins glitters here
+    description: They're gold coins.
+    handle: [coin, coins]
+    attr: [Stackable]
+    weight: 1
+    count: 12
+"#;
+        let (mut g, u) = game_with_items(x);
+
+        let mut bill = PlayerType::Human(Player::new());
+        bill.set_name("Bill");
+        let x = r#"---
+inner:
+- Holdable:
+    name: coin
+    display: A foreign coin lies here
+    description: It's stamped with a face you don't know.
+    handle: [coin, coins]
+    attr: [Stackable]
+    weight: 1
+"#;
+        for item in items(x) {
+            bill.insert_item(item).unwrap();
+        }
+        let bid = bill.uuid();
+        g.add_player(bill);
+
+        let (_, msg) = g.interpret(u, "inventory").unwrap();
+        assert!(msg.to_self().contains("coins glitters here (x12)"));
+        assert!(msg.to_self().contains("you are carrying 12"));
+
+        let (_, msg) = g.interpret(u, "drop 5 coin").unwrap();
+        assert!(msg.to_self().contains("you drop the coin (x5)"));
+
+        let (_, msg) = g.interpret(u, "give 3 coin bill").unwrap();
+        assert!(msg.to_self().contains("coin (x3)"));
+
+        let (_, msg) = g.interpret(u, "give 9 coin bill").unwrap();
+        assert!(msg.to_self().contains("you don't have 9 of the coin"));
+
+        let (_, msg) = g.interpret(u, "take 2 coin").unwrap();
+        assert!(msg.to_self().contains("you take the coin (x2)"));
+        assert!(msg.to_others().unwrap().contains("picks up a coin (x2)"));
+
+        let (_, msg) = g.interpret(u, "inventory").unwrap();
+        assert!(msg.to_self().contains("(x6)"));
+
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(msg.to_self().contains("(x3)"));
+
+        // the foreign coin doesn't join the gold ones
+        let (_, msg) = g.interpret(bid, "inventory").unwrap();
+        assert!(msg.to_self().contains("coins glitters here (x3)"));
+        assert!(msg.to_self().contains("A foreign coin lies here"));
+        assert!(!msg.to_self().contains("foreign coin lies here (x"));
+    }
+}
@@ -1,26 +1,40 @@
+mod admin;
 mod broadcast;
+mod chat;
 mod commands;
+mod door;
+mod food;
 mod item;
+mod shop;
+mod snapshot;
+mod social;
+mod tick;
 mod util;
 
+pub use snapshot::{Snapshot, SNAPSHOT_VERSION};
+pub use tick::TICK;
+
 use std::backtrace::Backtrace;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::{Error as StdError, Error};
 
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
 
 use rand::Rng;
 
+use crate::db::{DBResult, Pool, Storage};
 use crate::error::EnnuiError;
 use crate::error::EnnuiError::{Fatal, Lesser};
 use crate::fight::FightMessage;
-use crate::game::util::load_rooms;
-use crate::interpreter::CommandQuality::{Awake, Motion};
-use crate::interpreter::{CommandKind, CommandMessage, Interpreter};
-use crate::item::Item;
+pub use crate::game::util::{item_templates, load_npcs};
+use crate::game::util::{load_channels, load_rooms, load_socials, load_templates};
+use crate::interpreter::{CommandKind, CommandMessage, CommandQuality, Interpreter};
+use crate::item::template::Templates;
+use crate::item::{Item, Slot};
 use crate::list::{List, ListTrait};
 use crate::text::channel::DiscreteMessage;
 
@@ -33,13 +47,16 @@ use crate::map::list::{RoomList, RoomListTrait};
 use crate::map::{Room, Space};
 use crate::obstacle::door::{DoorState, GuardState, ObstacleState};
 use crate::player::list::{PlayerIdList, PlayerIdListTrait, PlayerList, PlayerListTrait};
-use crate::player::PlayerStatus::{Asleep, Dead, Sitting};
-use crate::player::{PlayerType, Uuid};
+use crate::player::PlayerStatus::WizInvis;
+use crate::player::{PlayerType, Role, Uuid, CORPSE_DECAY};
 use crate::text::article;
+use crate::text::chat::ChatChannel;
 use crate::text::message::{
     Audience, Broadcast, FightAudience, Message, MessageFormat, Messenger, Msg,
 };
-use crate::text::Color::{Green, Magenta};
+use crate::text::social::Social;
+use crate::text::Color::{Green, Magenta, Yellow};
+use crate::text::Wrap;
 use std::fmt::Debug;
 use std::mem::take;
 
@@ -68,16 +85,35 @@ pub struct Game {
     interpreter: Interpreter,
     fight_sender: Option<Sender<(FightAudience, FightMessage)>>,
     discrete_sender: Option<Sender<DiscreteMessage>>,
+    socials: Vec<Social>,
+    channels: Vec<ChatChannel>,
+    hunger: bool,
+    corpse_decay: u32,
+    templates: Arc<Templates>,
+    db: Arc<Pool>,
+    snapshot_path: Option<PathBuf>,
+    since_snapshot: u32,
+    /// Where players from the last snapshot were standing, by name, until they log in again
+    returning: HashMap<String, Coord>,
 }
 
 impl Game {
     pub fn new() -> GameResult<Self> {
         let (players, mut rooms) = (HashMap::new(), RoomList::default());
 
-        load_rooms(&mut rooms)?;
+        let db = Pool::from_env();
+        let templates = load_templates(&db)?;
+        load_rooms(&mut rooms, &templates)?;
+        let socials = load_socials()?;
+        let channels = load_channels()?;
 
         let mut interpreter = Interpreter::new();
         commands::fill_interpreter(&mut interpreter);
+        admin::fill_interpreter(&mut interpreter);
+        social::fill_interpreter(&mut interpreter);
+        chat::fill_interpreter(&mut interpreter);
+        food::fill_interpreter(&mut interpreter);
+        shop::fill_interpreter(&mut interpreter);
 
         let g = Self {
             players,
@@ -85,6 +121,15 @@ impl Game {
             interpreter,
             fight_sender: None,
             discrete_sender: None,
+            socials,
+            channels,
+            hunger: false,
+            corpse_decay: CORPSE_DECAY,
+            templates: Arc::new(templates),
+            db: Arc::new(db),
+            snapshot_path: None,
+            since_snapshot: 0,
+            returning: HashMap::new(),
         };
 
         Ok(g)
@@ -98,16 +143,92 @@ impl Game {
         self.discrete_sender = Some(sender);
     }
 
+    /// Sets how many ticks corpses last before they rot away
+    pub fn set_corpse_decay(&mut self, ticks: u32) {
+        self.corpse_decay = ticks;
+    }
+
+    pub fn templates(&self) -> &Templates {
+        &self.templates
+    }
+
+    /// Uses the given pool for the database, instead of the one `ENNUI_DB` names
+    pub fn set_db(&mut self, pool: Pool) {
+        self.db = Arc::new(pool);
+    }
+
+    /// The database pool, for work that shouldn't hold the game lock
+    pub fn db(&self) -> Arc<Pool> {
+        self.db.clone()
+    }
+
+    /// Does `f` with the database on a thread of its own, and writes whatever it gives back to
+    /// the player when it's done. The game carries on in the meantime, however slow the database
+    /// is. Anything that goes wrong with the database is logged, and the player is told so.
+    fn in_background<F>(&self, u: u128, mut f: F) -> Result<(), EnnuiError>
+    where
+        F: FnMut(&mut dyn Storage, &Mutex<PlayerType>) -> DBResult<String> + Send + 'static,
+    {
+        let p = self.get_player(u)?;
+        let pool = self.db.clone();
+
+        std::thread::spawn(move || {
+            let msg = pool.run(|db| f(db, &p)).unwrap_or_else(|e| {
+                print_err(lesser(&format!("database problem: {}", e)));
+                DB_TROUBLE.to_owned()
+            });
+            let msg = msg.padded().wrap(90);
+            if let Err(e) = p.lock().unwrap().write(msg.as_bytes()) {
+                print_err(e);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Turns on hunger and thirst for this world, for everyone already here and everyone who
+    /// joins later
+    pub fn enable_hunger(&mut self) {
+        self.hunger = true;
+        for p in self.players.values() {
+            let mut p = p.lock().unwrap();
+            if let PlayerType::Human(_) = *p {
+                p.enable_hunger();
+            }
+        }
+    }
+
     pub fn interpret(&mut self, p: u128, s: &str) -> Result<CommandMessage, EnnuiError> {
         let s = s.to_lowercase();
         eprintln!("executing command '{}' for player {}", s, p);
         eprintln!("in file {} on line number {}", file!(), line!());
 
-        let (cmd, args) = Interpreter::process_string_command(&s);
+        let (mut cmd, mut args) = Interpreter::process_string_command(&s);
+
+        // anything that isn't a command may still be the name of a channel or a social
+        if cmd == CommandKind::NotFound {
+            if let Some(word) = s.split_whitespace().next() {
+                if self.find_channel(p, word).is_some() {
+                    cmd = CommandKind::Channel;
+                    args.insert(0, word);
+                } else if self.find_social(word).is_some() {
+                    cmd = CommandKind::Social;
+                    args.insert(0, word);
+                }
+            }
+        }
+
+        // the command table is released before the command runs, so that a command may
+        // interpret another command on a player's behalf
+        let cmd_func = {
+            let commands = self.interpreter.commands();
+            let other_commands = commands.lock().ok()?;
+            other_commands.get(&cmd)?.clone()
+        };
 
-        let commands = self.interpreter.commands();
-        let other_commands = commands.lock().ok()?;
-        let cmd_func = other_commands.get(&cmd)?.lock().ok()?;
+        if let Some(msg) = self.verify_role(&cmd, p, &args)? {
+            return Ok(msg);
+        }
 
         if let Some(msg) = self.verify_status(cmd, p)? {
             return Ok(msg);
@@ -116,12 +237,52 @@ impl Game {
         (*cmd_func)(self, p, &args)
     }
 
+    /// Finds the first channel the player may use whose name begins with `word`
+    fn find_channel(&self, u: u128, word: &str) -> Option<&ChatChannel> {
+        let role = self.players.get(&u)?.lock().unwrap().role();
+        self.channels
+            .iter()
+            .find(|c| role >= c.role && c.name.starts_with(word))
+    }
+
+    /// Finds the first social whose name begins with `word`
+    fn find_social(&self, word: &str) -> Option<&Social> {
+        self.socials.iter().find(|s| s.name.starts_with(word))
+    }
+
+    /// Tells everyone that the game is going down, and flushes their streams
+    pub fn shutdown(&mut self) {
+        self.send(
+            &self.players.to_id_list(),
+            &"the game is shutting down. goodbye!".padded(),
+        );
+
+        for p in self.players.values() {
+            p.lock().unwrap().flush().unwrap_or_default();
+        }
+
+        self.save_snapshot();
+    }
+
     pub fn interpreter(&mut self) -> &mut Interpreter {
         &mut self.interpreter
     }
 
-    pub fn add_player(&mut self, p: PlayerType) {
-        self.rooms.entry(p.loc()).or_default().add_player(p.uuid());
+    pub fn add_player(&mut self, mut p: PlayerType) {
+        if let PlayerType::Human(_) = p {
+            for c in self.channels.iter().filter(|c| c.default) {
+                p.subscribe(&c.name);
+            }
+            if self.hunger {
+                p.enable_hunger();
+            }
+        }
+
+        let room = self.rooms.entry(p.loc()).or_default();
+        if let PlayerType::Npc(_) = p {
+            room.claim_resident(&p.handle(), p.uuid());
+        }
+        room.add_player(p.uuid());
         self.players.insert(p.uuid(), Arc::new(Mutex::new(p)));
     }
 
@@ -194,6 +355,12 @@ impl Game {
 
     pub fn set_player_name(&mut self, u: u128, name: &str) -> Result<(), EnnuiError> {
         self.get_player(u)?.lock().unwrap().set_name(name);
+        self.place_returning(u, name)
+    }
+
+    /// Gives the player a role. Only do this once they've logged in to an account with it.
+    pub fn set_player_role(&mut self, u: u128, role: Role) -> Result<(), EnnuiError> {
+        self.get_player(u)?.lock().unwrap().set_role(role);
         Ok(())
     }
 
@@ -251,7 +418,10 @@ impl Game {
 
             room.players_mut().remove(&p.uuid());
 
-            let corpse: Item = player.into();
+            let mut corpse: Item = player.into();
+            if let Some(t) = corpse.timer_mut() {
+                t.ticks = self.corpse_decay;
+            }
             room.insert_item(corpse);
         }
 
@@ -294,8 +464,12 @@ impl Game {
         Some(if let Some(item) = room.get_item(handle.into()) {
             let mut s = item.description();
             if let Item::Container(lst) = item {
-                s.push_str(&format!("\nthe {} is holding:\n", item.name()));
-                s.push_str(&lst.display_items());
+                if lst.lid().map_or(false, |l| !l.is_open()) {
+                    s.push_str("\nit's closed");
+                } else {
+                    s.push_str(&format!("\nthe {} is holding:\n", item.name()));
+                    s.push_str(&lst.display_items());
+                }
             }
             s
         } else {
@@ -313,6 +487,40 @@ impl Game {
             .map(|(_, p)| p.lock().unwrap().uuid())
     }
 
+    fn verify_role(
+        &self,
+        cmd: &CommandKind,
+        u: u128,
+        args: &[&str],
+    ) -> Result<Option<CommandMessage>, EnnuiError> {
+        let required = cmd.role();
+        if required == Role::Player {
+            return Ok(None);
+        }
+
+        let p = self.get_player(u)?;
+        let (name, role) = {
+            let p = p.lock().unwrap();
+            (p.name(), p.role())
+        };
+
+        if role < required {
+            return message(u, "you don't have permission to do that").map(Some);
+        }
+
+        eprintln!(
+            "[{}]: {} ({:?}) used {:?} with target '{}'",
+            "PRIVILEGED".color(Yellow),
+            name,
+            role,
+            cmd,
+            args.join(" ")
+        );
+        eprintln!("in file {} on line number {}", file!(), line!());
+
+        Ok(None)
+    }
+
     fn verify_status(
         &self,
         cmd: CommandKind,
@@ -320,18 +528,17 @@ impl Game {
     ) -> Result<Option<CommandMessage>, EnnuiError> {
         let p = self.get_player(u)?;
         let p = p.lock().unwrap();
-        let cnv = |m: Result<CommandMessage, EnnuiError>| m.map(Some);
+        let requirements = cmd.requirements();
 
-        if p.is(Dead) && cmd != CommandKind::Quit {
-            return cnv(message(u, "oh boy. you can't move. you're dead."));
-        }
-
-        if p.is(Asleep) && cmd.is(Awake) {
-            return cnv(message(u, "you're asleep. why not just sleep?"));
-        }
+        for quality in CommandQuality::all() {
+            if !requirements.contains(quality) {
+                continue;
+            }
 
-        if p.is(Sitting) && cmd.is(Motion) {
-            return cnv(message(u, "why don't you try standing up first?"));
+            if let Some(status) = quality.conflicts().iter().find(|&&s| p.is(s)) {
+                let msg = format!("you can't do that while {}", status);
+                return message(u, msg).map(Some);
+            }
         }
 
         Ok(None)
@@ -363,11 +570,30 @@ impl Game {
         let u = u.uuid();
         let loc = self.loc_of(u)?;
         let name = self.name_of(u)?;
+        let invisible = self.get_player(u)?.is(WizInvis);
+
+        let (cost, movement) = {
+            let p = self.get_player(u)?;
+            let p = p.lock().unwrap();
+            (p.move_cost(), p.movement())
+        };
+        if movement < cost {
+            return message(
+                u,
+                "you're too exhausted to go any further. perhaps a nap would help",
+            );
+        }
 
         let mut other_msg = None;
         let mut terminate = None;
 
-        let msg: Cow<'static, str> = match self.move_player(loc, u, dir) {
+        let moved = self.move_player(loc, u, dir);
+        if moved.is_ok() {
+            self.get_player(u)?.lock().unwrap().tire(cost);
+        }
+
+        let msg: Cow<'static, str> = match moved {
+            Ok(_) if invisible => format!("you go {:?}\n\n{}", dir, self.describe_room(u)?).into(),
             Ok(_) => {
                 other_msg = Some(format!("{} exits {}", name, dir));
                 format!("you go {:?}\n\n{}", dir, self.describe_room(u)?).into()
@@ -401,16 +627,16 @@ impl Game {
         let msg = FightMessage {
             s: msg.into(),
             obj: None,
-            oth: other_msg.clone().map(|s| s.padded().into()),
+            oth: other_msg.map(|s| s.padded().into()),
         };
 
         let return_msg = message(aud, msg);
-        if terminate.is_some() {
+        if terminate.is_some() || invisible {
             return return_msg;
         }
 
         let next_room_aud = {
-            if let Some(next_room) = self.rooms.get(&loc.add(dir)?) {
+            if let Some(next_room) = self.rooms.get(&self.rooms.dest(loc, dir)?) {
                 Audience(0, next_room.players_except(u))
             } else {
                 return return_msg;
@@ -444,9 +670,21 @@ impl Game {
         let mut item_list = String::new();
         item_list.push_str(&p.display_items());
 
+        let mut worn = String::new();
+        if !p.clothing().list().is_empty() {
+            worn.push_str(&format!("\n{} is wearing:", p.name()));
+            for slot in Slot::ALL.iter() {
+                if let Some(item) = p.worn(*slot) {
+                    let line = format!("{:<8}{}", slot, article(&item.name()));
+                    worn.push_str(&format!("\n{}", line.color(Green)));
+                }
+            }
+        }
+
         Some(format!(
-            "{}{}{}",
+            "{}{}{}{}",
             description,
+            worn,
             item_list_title,
             item_list.color(Green)
         ))
@@ -457,10 +695,18 @@ impl Game {
         s.push_str("you are holding:");
 
         let player = self.get_player(u.uuid())?;
-
-        let items = player.lock().unwrap().display_items();
-
-        s.push_str(&items);
+        let player = player.lock().unwrap();
+
+        s.push_str(&player.display_items());
+        s.push_str(&format!(
+            "\n\nyou are carrying {} of a possible {}",
+            player.carried_weight(),
+            player.carry_limit()
+        ));
+        s.push_str(&format!("\nyou have {} gold", player.gold()));
+        if player.is_encumbered() {
+            s.push_str("\nyou are weighed down by everything you're carrying");
+        }
 
         Ok(s)
     }
@@ -480,7 +726,7 @@ impl Game {
     }
 
     fn move_player(&mut self, loc: Coord, u: u128, dir: MapDir) -> Result<(), DoorState> {
-        let next_coord = loc.add(dir);
+        let next_coord = self.rooms.dest(loc, dir);
 
         self.check_doors(loc, next_coord, dir)?;
 
@@ -536,6 +782,11 @@ impl Game {
             }
         }
 
+        // an exit that leads somewhere other than its neighbour has no door on the far side
+        if self.rooms.off_grid(loc, dir) {
+            return Ok(());
+        }
+
         {
             let dst_room = self.rooms.get(&next_coord?)?;
             if let Some(door) = dst_room.doors().get(&dir.opposite()) {
@@ -567,6 +818,14 @@ impl Game {
     }
 }
 
+/// What players are told when the database lets them down
+const DB_TROUBLE: &str = "There was an error with the database. Please file an issue at github.com/pmengelbert/ennui, or email peter@engelbert.dev";
+
+/// Output that nobody sees, for commands that answer later on their own
+pub fn no_message() -> Result<GameOutput, EnnuiError> {
+    message(Audience(0, vec![]), "")
+}
+
 pub fn message<A: 'static, M: 'static>(aud: A, msg: M) -> Result<GameOutput, EnnuiError>
 where
     A: Messenger,
@@ -599,3 +858,40 @@ pub fn print_err<T: Error + Debug>(err: T) {
     eprintln!("[{}]: {}", "ERROR".color(Magenta), err);
     eprintln!("in file {} on line number {}", file!(), line!());
 }
+
+#[cfg(test)]
+pub mod game_test {
+    use super::*;
+    use crate::attribute::Quality;
+    use crate::item::YamlItemList;
+    use crate::player::{Player, PlayerStatus};
+
+    /// A game with one nameless player in it, in the given states
+    pub fn game_with_player(status: &[PlayerStatus]) -> (Game, u128) {
+        let mut g = Game::new().unwrap();
+        let mut p = PlayerType::Human(Player::new());
+        for s in status {
+            p.set_attr(*s);
+        }
+        let u = p.uuid();
+        g.add_player(p);
+        (g, u)
+    }
+
+    /// A game with one nameless player in it, carrying the items written out in `yaml`
+    pub fn game_with_items(yaml: &str) -> (Game, u128) {
+        let (g, u) = game_with_player(&[]);
+        {
+            let mut p = g.players.get(&u).unwrap().lock().unwrap();
+            for item in items(yaml) {
+                p.insert_item(item).unwrap();
+            }
+        }
+        (g, u)
+    }
+
+    /// The items written out in `yaml`, as a list with an `inner` key
+    pub fn items(yaml: &str) -> List<Item, Quality> {
+        serde_yaml::from_str::<YamlItemList>(yaml).unwrap().into()
+    }
+}
@@ -0,0 +1,291 @@
+use super::*;
+
+pub fn fill_interpreter(i: &mut Interpreter) {
+    i.insert("list", |g, u, _| g.list_stock(u));
+    i.insert("buy", |g, u, a| g.buy(u, a));
+    i.insert("sell", |g, u, a| g.sell(u, a));
+    i.insert("value", |g, u, a| g.value(u, a));
+}
+
+const NO_SHOP: &str = "there's nobody here to trade with";
+
+impl Game {
+    /// The first shopkeeper in the room, other than the player
+    fn shopkeeper(&self, u: u128) -> Result<Option<Arc<Mutex<PlayerType>>>, EnnuiError> {
+        let loc = self.loc_of(u)?;
+        Ok(self
+            .rooms
+            .player_ids(loc)
+            .iter()
+            .filter(|id| **id != u)
+            .filter_map(|id| self.players.get(id))
+            .find(|p| p.lock().unwrap().shop().is_some())
+            .cloned())
+    }
+
+    fn list_stock(&mut self, u: u128) -> Result<GameOutput, EnnuiError> {
+        let keeper = match self.shopkeeper(u)? {
+            Some(keeper) => keeper,
+            None => return message(u, NO_SHOP),
+        };
+        let keeper = keeper.lock().unwrap();
+        let shop = keeper.shop().ok_or_else(|| fatal("SHOP NOT FOUND"))?;
+
+        let stock = keeper.list();
+        if stock.is_empty() {
+            return message(u, format!("{} has nothing for sale", keeper.name()));
+        }
+
+        let mut s = format!("{} has for sale:", keeper.name());
+        for item in stock {
+            let price = match shop.buy_price(item.price()) {
+                Some(price) => format!("{} gold", price),
+                None => "priceless".to_owned(),
+            };
+            s.push_str(&format!("\n    {:<30}{}", item.name().color(Green), price));
+        }
+
+        message(u, s)
+    }
+
+    fn buy(&mut self, u: u128, a: &[&str]) -> Result<GameOutput, EnnuiError> {
+        let handle = match a {
+            [handle] => *handle,
+            _ => return message(u, "what do you want to buy?"),
+        };
+        let keeper = match self.shopkeeper(u)? {
+            Some(keeper) => keeper,
+            None => return message(u, NO_SHOP),
+        };
+        let mut keeper = keeper.lock().unwrap();
+        let p = self.get_player(u)?;
+        let mut p = p.lock().unwrap();
+
+        let (price, weight) = {
+            let shop = keeper.shop().ok_or_else(|| fatal("SHOP NOT FOUND"))?;
+            match keeper.get_item(handle.into()) {
+                Some(item) => (shop.buy_price(item.price()), Self::weight_of(item, 1)),
+                None => {
+                    let msg = format!("{} doesn't sell {}", keeper.name(), article(handle));
+                    return message(u, msg);
+                }
+            }
+        };
+
+        let price = match price {
+            Some(price) => price,
+            None => return message(u, priceless(&keeper.name(), handle)),
+        };
+        if p.gold() < price {
+            let msg = format!(
+                "you can't afford {}. it costs {} gold",
+                article(handle),
+                price
+            );
+            return message(u, msg);
+        }
+
+        if !p.can_carry(weight) {
+            return message(u, format!("{} is too heavy for you", article(handle)));
+        }
+
+        let item = keeper.get_some_owned(handle.into(), 1)?;
+        let item_name = item.name();
+        if let Err(item) = p.insert_item(item) {
+            keeper.insert_item(item).ok();
+            return Err(fatal("COULD NOT GIVE BOUGHT ITEM TO PLAYER"));
+        }
+        p.pay(price);
+        keeper.earn(price);
+
+        let others = self.rooms.player_ids(p.loc()).except(u);
+        let msg = Msg {
+            s: format!("you buy the {} for {} gold", item_name, price),
+            o: Some(format!(
+                "{} buys {} from {}",
+                p.name(),
+                article(&item_name),
+                keeper.name()
+            )),
+        };
+
+        message(Audience(u, others), msg)
+    }
+
+    fn sell(&mut self, u: u128, a: &[&str]) -> Result<GameOutput, EnnuiError> {
+        let handle = match a {
+            [handle] => *handle,
+            _ => return message(u, "what do you want to sell?"),
+        };
+        let keeper = match self.shopkeeper(u)? {
+            Some(keeper) => keeper,
+            None => return message(u, NO_SHOP),
+        };
+        let mut keeper = keeper.lock().unwrap();
+        let p = self.get_player(u)?;
+        let mut p = p.lock().unwrap();
+
+        let price = {
+            let shop = keeper.shop().ok_or_else(|| fatal("SHOP NOT FOUND"))?;
+            match p.get_item(handle.into()) {
+                Some(item) => shop.sell_price(item.price()),
+                None => return message(u, format!("you're not holding {}", article(handle))),
+            }
+        };
+
+        let price = match price {
+            Some(0) => {
+                let msg = format!("{} isn't interested in {}", keeper.name(), article(handle));
+                return message(u, msg);
+            }
+            Some(price) => price,
+            None => return message(u, priceless(&keeper.name(), handle)),
+        };
+
+        let item = p.get_some_owned(handle.into(), 1)?;
+        let item_name = item.name();
+        if let Err(item) = keeper.insert_item(item) {
+            p.insert_item(item).ok();
+            return Err(fatal("COULD NOT GIVE SOLD ITEM TO SHOPKEEPER"));
+        }
+        p.earn(price);
+
+        let others = self.rooms.player_ids(p.loc()).except(u);
+        let msg = Msg {
+            s: format!("you sell the {} for {} gold", item_name, price),
+            o: Some(format!(
+                "{} sells {} to {}",
+                p.name(),
+                article(&item_name),
+                keeper.name()
+            )),
+        };
+
+        message(Audience(u, others), msg)
+    }
+
+    /// Says what an item would fetch, if it's the player's, or cost, if it's the shopkeeper's
+    fn value(&mut self, u: u128, a: &[&str]) -> Result<GameOutput, EnnuiError> {
+        let handle = match a {
+            [handle] => *handle,
+            _ => return message(u, "what do you want valued?"),
+        };
+        let keeper = match self.shopkeeper(u)? {
+            Some(keeper) => keeper,
+            None => return message(u, NO_SHOP),
+        };
+        let keeper = keeper.lock().unwrap();
+        let shop = keeper.shop().ok_or_else(|| fatal("SHOP NOT FOUND"))?;
+        let p = self.get_player(u)?;
+        let p = p.lock().unwrap();
+
+        let msg = if let Some(item) = p.get_item(handle.into()) {
+            match shop.sell_price(item.price()) {
+                Some(0) => format!("{} isn't interested in {}", keeper.name(), article(handle)),
+                None => priceless(&keeper.name(), handle),
+                Some(price) => format!(
+                    "{} would give you {} gold for the {}",
+                    keeper.name(),
+                    price,
+                    item.name()
+                ),
+            }
+        } else if let Some(item) = keeper.get_item(handle.into()) {
+            match shop.buy_price(item.price()) {
+                Some(price) => format!("the {} costs {} gold", item.name(), price),
+                None => priceless(&keeper.name(), handle),
+            }
+        } else {
+            format!("you don't see {} for sale", article(handle))
+        };
+
+        message(u, msg)
+    }
+}
+
+/// What's said when an item is worth more gold than can be counted
+fn priceless(keeper: &str, handle: &str) -> String {
+    format!("{} can't put a price on {}", keeper, article(handle))
+}
+
+#[cfg(test)]
+mod shop_test {
+    use crate::game::game_test::game_with_items;
+
+    #[test]
+    fn test_shop() {
+        use crate::player::npc::YamlPlayer;
+
+        let x = r#"---
+inner:
+- Holdable:
+    name: rock
+    display: A rock lies here
+    description: It's a rock.
+    handle: [rock]
+- Holdable:
+    name: ring
+    display: A ring lies here
+    description: It's a gold ring.
+    handle: [ring]
+    price: 20
+- Holdable:
+    name: gem
+    display: A gem lies here
+    description: It's a flawless gem.
+    handle: [gem]
+    price: 18446744073709551615
+"#;
+        let (mut g, u) = game_with_items(x);
+        let gold = g.get_player(u).unwrap().lock().unwrap().gold();
+
+        let (_, msg) = g.interpret(u, "list").unwrap();
+        assert!(msg.to_self().contains("nobody here"));
+
+        let bob = r#"---
+name: Bob
+handle: [bob]
+description: ""
+display: Bob is here, minding the shop
+shop:
+  stock:
+  - Edible:
+      name: bread
+      display: A loaf of bread sits here
+      description: It's a loaf of bread.
+      handle: [bread]
+      price: 5
+"#;
+        let keeper: YamlPlayer = serde_yaml::from_str(bob).unwrap();
+        g.add_player(keeper.into());
+
+        let (_, msg) = g.interpret(u, "list").unwrap();
+        assert!(msg.to_self().contains("bread"));
+        assert!(msg.to_self().contains("6 gold"));
+
+        let (_, msg) = g.interpret(u, "buy bread").unwrap();
+        assert!(msg.to_self().contains("you buy the bread for 6 gold"));
+
+        let (_, msg) = g.interpret(u, "list").unwrap();
+        assert!(msg.to_self().contains("nothing for sale"));
+
+        let (_, msg) = g.interpret(u, "value rock").unwrap();
+        assert!(msg.to_self().contains("isn't interested"));
+
+        let (_, msg) = g.interpret(u, "value ring").unwrap();
+        assert!(msg.to_self().contains("10 gold"));
+
+        let (_, msg) = g.interpret(u, "value gem").unwrap();
+        assert!(msg.to_self().contains("can't put a price"));
+
+        let (_, msg) = g.interpret(u, "sell gem").unwrap();
+        assert!(msg.to_self().contains("can't put a price"));
+
+        let (_, msg) = g.interpret(u, "sell ring").unwrap();
+        assert!(msg.to_self().contains("you sell the ring for 10 gold"));
+
+        let (_, msg) = g.interpret(u, "inventory").unwrap();
+        let left = format!("you have {} gold", gold + 4);
+        assert!(msg.to_self().contains(&left));
+    }
+}
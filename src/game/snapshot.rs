@@ -0,0 +1,341 @@
+use super::*;
+use crate::item::YamlItemList;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The version written into every snapshot. Snapshots of any other version aren't restored.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// How many ticks go by between snapshots
+pub const SNAPSHOT_EVERY: u32 = 10;
+
+/// `Snapshot` is the state of the world at a moment: what's lying in every room, which doors and
+/// guards are open, where the NPCs are and how they're faring, and where each player was standing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub rooms: Vec<RoomSnapshot>,
+    pub players: Vec<PlayerSnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomSnapshot {
+    pub loc: Coord,
+    pub items: YamlItemList,
+    #[serde(default)]
+    pub doors: HashMap<MapDir, DoorState>,
+}
+
+/// Where a player or NPC was. Only NPCs have their hit points kept, since players who come back
+/// are fresh from the login screen anyway.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub name: String,
+    pub npc: bool,
+    pub loc: Coord,
+    #[serde(default)]
+    pub hp: Option<i64>,
+}
+
+impl Snapshot {
+    /// Reads a snapshot from a file, as YAML if it ends in `.yaml` and as CBOR otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> GameResult<Self> {
+        let bytes = std::fs::read(&path)?;
+        let snapshot: Self = if is_yaml(path.as_ref()) {
+            serde_yaml::from_slice(&bytes)?
+        } else {
+            serde_cbor::from_slice(&bytes)?
+        };
+
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Box::new(fatal(&format!(
+                "snapshot is version {}, but only version {} can be restored",
+                snapshot.version, SNAPSHOT_VERSION
+            ))));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot to a file, replacing any snapshot that was there only once it's done
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> GameResult<()> {
+        let path = path.as_ref();
+        write_snapshot(path, &self.to_bytes(path)?)
+    }
+
+    /// The snapshot as it's written to the given file
+    fn to_bytes(&self, path: &Path) -> GameResult<Vec<u8>> {
+        if is_yaml(path) {
+            Ok(serde_yaml::to_vec(self)?)
+        } else {
+            Ok(serde_cbor::to_vec(self)?)
+        }
+    }
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml") | Some("yml")
+    )
+}
+
+/// Writes to a file beside the snapshot first, so that one cut short never replaces a whole one
+fn write_snapshot(path: &Path, bytes: &[u8]) -> GameResult<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+impl Game {
+    /// Takes snapshots to the given file, every `SNAPSHOT_EVERY` ticks and on shutdown
+    pub fn set_snapshot_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.snapshot_path = Some(path.into());
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let rooms = self
+            .rooms
+            .values()
+            .map(|r| RoomSnapshot {
+                loc: r.loc(),
+                items: r.items().into(),
+                doors: r
+                    .doors()
+                    .iter()
+                    .map(|(d, door)| (*d, door.state()))
+                    .collect(),
+            })
+            .collect();
+
+        let players = self
+            .players
+            .values()
+            .filter_map(|p| {
+                let p = p.lock().unwrap();
+                let npc = match &*p {
+                    PlayerType::Npc(_) => true,
+                    PlayerType::Human(_) if !p.name().is_empty() => false,
+                    _ => return None,
+                };
+
+                Some(PlayerSnapshot {
+                    name: p.name(),
+                    npc,
+                    loc: p.loc(),
+                    hp: if npc { Some(p.hp()) } else { None },
+                })
+            })
+            .collect();
+
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            rooms,
+            players,
+        }
+    }
+
+    /// Puts the world back the way the snapshot has it. Rooms and doors that are no longer on the
+    /// map are passed over, as are NPCs who aren't around. Players are put back where they were
+    /// when they next log in.
+    pub fn restore(&mut self, snapshot: Snapshot) -> Result<(), EnnuiError> {
+        for saved in snapshot.rooms {
+            let room = match self.rooms.get_mut(&saved.loc) {
+                Some(room) => room,
+                None => continue,
+            };
+
+            room.set_items(saved.items.into());
+            for (dir, state) in saved.doors {
+                if let Some(door) = room.doors_mut().get_mut(&dir) {
+                    door.set_state(state);
+                }
+            }
+        }
+
+        let mut placed = vec![];
+        for saved in snapshot.players {
+            if !saved.npc {
+                self.returning.insert(saved.name.to_lowercase(), saved.loc);
+                continue;
+            }
+
+            let id = self.players.iter().find_map(|(id, p)| {
+                let p = p.lock().unwrap();
+                let found = matches!(&*p, PlayerType::Npc(_))
+                    && p.name() == saved.name
+                    && !placed.contains(id);
+                if found {
+                    Some(*id)
+                } else {
+                    None
+                }
+            });
+            let id = match id {
+                Some(id) => id,
+                None => continue,
+            };
+            placed.push(id);
+
+            self.place(id, saved.loc)?;
+            if let Some(hp) = saved.hp {
+                self.get_player(id)?.lock().unwrap().set_hp(hp);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Puts a player who has logged in again back where they were when the last snapshot was
+    /// taken
+    pub(super) fn place_returning(&mut self, u: u128, name: &str) -> Result<(), EnnuiError> {
+        match self.returning.remove(&name.to_lowercase()) {
+            Some(loc) => self.place(u, loc),
+            None => Ok(()),
+        }
+    }
+
+    /// Moves a player straight to the given room, if it's still there
+    fn place(&mut self, u: u128, dest: Coord) -> Result<(), EnnuiError> {
+        if !self.rooms.contains_key(&dest) {
+            return Ok(());
+        }
+
+        let loc = self.loc_of(u)?;
+        self.get_room_mut(loc)?.players_mut().remove(&u);
+        Self::do_player_move(&mut self.players, u, Some(dest), &mut self.rooms)
+            .map_err(|_| fatal("UNABLE TO PLACE PLAYER"))
+    }
+
+    /// Takes a snapshot, if snapshots are wanted, and waits for it to be saved. Anything that goes
+    /// wrong is only logged, so that the game carries on.
+    pub fn save_snapshot(&self) {
+        if let Some((path, bytes)) = self.take_snapshot() {
+            log_snapshot(write_snapshot(&path, &bytes));
+        }
+    }
+
+    /// Counts down to the next snapshot, and takes it when it's due. Only the taking is done here;
+    /// it's written out on a thread of its own, so that the game isn't held up waiting on the disk.
+    pub(super) fn tick_snapshot(&mut self) {
+        self.since_snapshot += 1;
+        if self.since_snapshot < SNAPSHOT_EVERY {
+            return;
+        }
+        self.since_snapshot = 0;
+
+        if let Some((path, bytes)) = self.take_snapshot() {
+            std::thread::spawn(move || log_snapshot(write_snapshot(&path, &bytes)));
+        }
+    }
+
+    /// The file to write a snapshot to and the snapshot itself, if snapshots are wanted
+    fn take_snapshot(&self) -> Option<(PathBuf, Vec<u8>)> {
+        let path = self.snapshot_path.clone()?;
+        match self.snapshot().to_bytes(&path) {
+            Ok(bytes) => Some((path, bytes)),
+            Err(e) => {
+                print_err(lesser(&format!("unable to take snapshot: {}", e)));
+                None
+            }
+        }
+    }
+}
+
+fn log_snapshot(result: GameResult<()>) {
+    if let Err(e) = result {
+        print_err(lesser(&format!("unable to save snapshot: {}", e)));
+    }
+}
+
+#[cfg(test)]
+mod snapshot_test {
+    use super::*;
+    use crate::game::game_test::items;
+    use crate::player::npc::YamlPlayer;
+    use crate::player::Player;
+
+    #[test]
+    fn test_snapshot() {
+        let bob = r#"---
+name: Bob
+handle: [bob]
+description: ""
+display: Bob is here
+ai_type: Static
+"#;
+        let corridor = Coord(0, 1, 0);
+
+        let mut g = Game::new().unwrap();
+        let mut alice = PlayerType::Human(Player::new());
+        alice.set_name("Alice");
+        alice.set_loc(corridor);
+        let x = r#"---
+inner:
+- Holdable:
+    name: pebble
+    display: A shiny pebble lies here
+    description: It's a pebble.
+    handle: [pebble]
+"#;
+        for item in items(x) {
+            alice.insert_item(item).unwrap();
+        }
+        let u = alice.uuid();
+        g.add_player(alice);
+        g.interpret(u, "drop pebble").unwrap();
+
+        let keeper: YamlPlayer = serde_yaml::from_str(bob).unwrap();
+        let keeper: PlayerType = keeper.into();
+        let b = keeper.uuid();
+        g.add_player(keeper);
+        let bob_p = g.players_mut().get(&b).unwrap().clone();
+        bob_p.lock().unwrap().set_loc(corridor);
+        bob_p.lock().unwrap().set_hp(3);
+
+        let mut snapshot = g.snapshot();
+        let cell = snapshot
+            .rooms
+            .iter_mut()
+            .find(|r| r.loc == Coord(0, 0, 0))
+            .unwrap();
+        assert_eq!(cell.doors[&MapDir::North], DoorState::Locked);
+        cell.doors.insert(MapDir::North, DoorState::Open);
+
+        let dir = std::env::temp_dir();
+        let cbor = dir.join(format!("ennui-snapshot-{}.cbor", std::process::id()));
+        snapshot.save(&cbor).unwrap();
+        assert_eq!(Snapshot::load(&cbor).unwrap().players.len(), 2);
+        std::fs::remove_file(&cbor).unwrap();
+
+        let yaml = dir.join(format!("ennui-snapshot-{}.yaml", std::process::id()));
+        snapshot.save(&yaml).unwrap();
+        assert!(std::fs::read_to_string(&yaml)
+            .unwrap()
+            .contains("version: 1"));
+        let snapshot = Snapshot::load(&yaml).unwrap();
+        std::fs::remove_file(&yaml).unwrap();
+
+        let mut g = Game::new().unwrap();
+        let keeper: YamlPlayer = serde_yaml::from_str(bob).unwrap();
+        let keeper: PlayerType = keeper.into();
+        let b = keeper.uuid();
+        g.add_player(keeper);
+        g.restore(snapshot).unwrap();
+
+        let door = &g.get_room(Coord(0, 0, 0)).unwrap().doors()[&MapDir::North];
+        assert_eq!(door.state(), DoorState::Open);
+
+        let bob_p = g.players_mut().get(&b).unwrap().clone();
+        assert_eq!(bob_p.lock().unwrap().loc(), corridor);
+        assert_eq!(bob_p.lock().unwrap().hp(), 3);
+
+        let alice = PlayerType::Human(Player::new());
+        let u = alice.uuid();
+        g.add_player(alice);
+        g.set_player_name(u, "alice").unwrap();
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(msg.to_self().contains("shiny pebble"));
+        assert!(msg.to_self().contains("Bob is here"));
+    }
+}
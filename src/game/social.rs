@@ -0,0 +1,93 @@
+use super::*;
+use crate::game::util::random_insult;
+use crate::interpreter::CommandKind;
+
+pub fn fill_interpreter(i: &mut Interpreter) {
+    i.insert("emote", |g, u, a| {
+        if a.is_empty() {
+            return message(u, "Usage: emote <action>");
+        }
+
+        let loc = g.loc_of(u)?;
+        let others = g.rooms.player_ids(loc).except(u);
+        let emote = format!("{} {}", g.name_of(u)?, a.join(" "));
+
+        let msg = Msg {
+            s: emote.clone(),
+            o: Some(emote),
+        };
+
+        message(Audience(u, others), msg)
+    });
+
+    i.insert("socials", |g, u, _| {
+        let mut ret = String::from("SOCIALS\n-------");
+        for s in &g.socials {
+            ret.push('\n');
+            ret.push_str(&s.name);
+        }
+
+        message(u, ret)
+    });
+
+    i.insert_kind(CommandKind::Social, |g, u, a| {
+        let social = match a.first().and_then(|w| g.find_social(w)) {
+            Some(s) => s.clone(),
+            None => return message(u, random_insult()),
+        };
+
+        let name = g.name_of(u)?;
+        let loc = g.loc_of(u)?;
+
+        let target = match a.get(1) {
+            None => {
+                let others = g.rooms.player_ids(loc).except(u);
+                return message(Audience(u, others), social.untargeted(&name));
+            }
+            Some(t) => *t,
+        };
+
+        let other = match g.id_of_in(loc, target) {
+            Some(id) => id,
+            None => return message(u, format!("you don't see {} here", target)),
+        };
+
+        if other == u {
+            let others = g.rooms.player_ids(loc).except(u);
+            return match social.reflexive(&name) {
+                Some(msg) => message(Audience(u, others), msg),
+                None => message(u, format!("you can't {} yourself", social.name)),
+            };
+        }
+
+        let other_name = g.name_of(other)?;
+        let others = g.rooms.player_ids(loc).except(u).except(other);
+        match social.targeted(&name, &other_name) {
+            Some(msg) => message(FightAudience(u, other, others.into_iter().collect()), msg),
+            None => message(u, format!("you can't {} at someone", social.name)),
+        }
+    });
+}
+
+#[cfg(test)]
+mod social_test {
+    use crate::game::game_test::game_with_player;
+    use crate::player::PlayerStatus;
+
+    #[test]
+    fn test_socials() {
+        let (mut g, u) = game_with_player(&[]);
+        let (_, msg) = g.interpret(u, "smi").unwrap();
+        assert!(msg.to_self().contains("you smile happily"));
+
+        let (_, msg) = g.interpret(u, "bow nobody").unwrap();
+        assert!(msg.to_self().contains("you don't see nobody here"));
+
+        let (_, msg) = g.interpret(u, "soc").unwrap();
+        assert!(msg.to_self().contains("wave"));
+
+        let (mut g, u) = game_with_player(&[PlayerStatus::Asleep]);
+        let (_, msg) = g.interpret(u, "wave").unwrap();
+        assert!(msg.to_self().contains("you can't do that while asleep"));
+    }
+}
@@ -0,0 +1,240 @@
+use super::util::npc_templates;
+use super::*;
+use std::time::Duration;
+
+/// How often the world moves along on its own
+pub const TICK: Duration = Duration::from_secs(30);
+
+impl Game {
+    /// Moves the world along by one tick. Poison and buffs wear on, players get hungrier, and
+    /// anyone who doesn't survive it is killed. Items wear away, rooms that are due are reset,
+    /// and a snapshot is taken every so often.
+    pub fn tick(&mut self) -> Result<(), EnnuiError> {
+        let mut felt = vec![];
+        let mut dead = vec![];
+
+        for (id, p) in self.players.iter() {
+            let mut p = p.lock().unwrap();
+            let alive = p.hp() > 0;
+            let msgs = p.tick();
+
+            if alive && p.hp() <= 0 {
+                dead.push(*id);
+            }
+            if !msgs.is_empty() {
+                felt.push((*id, msgs.join("\n")));
+            }
+        }
+
+        for (id, msg) in felt {
+            self.send(&id, &msg.padded());
+        }
+
+        for id in dead {
+            self.kill_player(id)?;
+        }
+
+        let mut seen = vec![];
+        for room in self.rooms.values_mut() {
+            let msgs = room.tick_items();
+            if !msgs.is_empty() {
+                seen.push((room.players().clone(), msgs.join("\n")));
+            }
+        }
+        for (ids, msg) in seen {
+            self.send(&ids, &msg.padded());
+        }
+
+        self.tick_snapshot();
+        self.reset_rooms()
+    }
+
+    /// Resets the rooms that are due, and brings back any NPCs living there who have been killed
+    fn reset_rooms(&mut self) -> Result<(), EnnuiError> {
+        let players = &self.players;
+        let mut missing = vec![];
+        let mut reset = vec![];
+
+        for (loc, room) in self.rooms.iter_mut() {
+            let occupied = room.players().iter().any(|id| {
+                players.get(id).map_or(false, |p| {
+                    matches!(*p.lock().unwrap(), PlayerType::Human(_))
+                })
+            });
+            let alive = |id| players.contains_key(&id);
+            if let Some(npcs) = room.tick_reset(occupied, alive) {
+                missing.extend(npcs.into_iter().map(|handle| (*loc, handle)));
+                reset.push(*loc);
+            }
+        }
+
+        // the far sides of the doors that were reset follow along
+        for loc in reset {
+            let doors: Vec<(MapDir, DoorState)> = self
+                .get_room(loc)?
+                .doors()
+                .iter()
+                .map(|(dir, door)| (*dir, door.state()))
+                .collect();
+
+            for (dir, state) in doors {
+                if self.rooms.off_grid(loc, dir) {
+                    continue;
+                }
+                let far = match self.rooms.dest(loc, dir) {
+                    Some(far) => far,
+                    None => continue,
+                };
+                if let Some(twin) = self
+                    .rooms
+                    .get_mut(&far)
+                    .and_then(|r| r.doors_mut().get_mut(&dir.opposite()))
+                {
+                    twin.set_state(state);
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let sender = match &self.discrete_sender {
+            Some(sender) => sender,
+            None => return Ok(()),
+        };
+
+        for (loc, handle) in missing {
+            // each room gets its own, so rooms sharing an NPC's handle all see one come back
+            let templates = npc_templates(&self.templates).map_err(|e| fatal(&format!("{}", e)))?;
+            let mut template = match templates
+                .into_iter()
+                .find(|t| handle.as_str() == t.info.handle)
+            {
+                Some(t) => t,
+                None => continue,
+            };
+
+            template.loc = loc;
+            sender
+                .send(DiscreteMessage::SpawnNpc(Box::new(template.into())))
+                .map_err(|_| fatal("UNABLE TO SPAWN NPC"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tick_test {
+    use super::*;
+    use crate::game::game_test::{game_with_player, items};
+    use crate::player::Player;
+
+    #[test]
+    fn test_room_reset() {
+        let (mut g, u) = game_with_player(&[]);
+
+        let (_, msg) = g.interpret(u, "take sword").unwrap();
+        assert!(msg.to_self().contains("you take the rusty sword"));
+        let (_, msg) = g.interpret(u, "take key corpse").unwrap();
+        assert!(msg.to_self().contains("skeleton key"));
+        g.interpret(u, "unlock north").unwrap();
+        let (_, msg) = g.interpret(u, "open north").unwrap();
+        assert!(msg.to_self().contains("swings open"));
+
+        // nothing comes back while someone is watching
+        for _ in 0..20 {
+            g.tick().unwrap();
+        }
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(!msg.to_self().contains("rusty sword"));
+
+        g.remove_player(u);
+        g.tick().unwrap();
+
+        let p = PlayerType::Human(Player::new());
+        let u = p.uuid();
+        g.add_player(p);
+
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(msg.to_self().contains("rusty sword"));
+        let (_, msg) = g.interpret(u, "take key corpse").unwrap();
+        assert!(msg.to_self().contains("skeleton key"));
+        let (_, msg) = g.interpret(u, "open north").unwrap();
+        assert!(msg.to_self().contains("that door is locked"));
+    }
+
+    #[test]
+    fn test_npc_respawn() {
+        let (mut g, _) = game_with_player(&[]);
+        let (tx, rx) = std::sync::mpsc::channel();
+        g.set_discrete_sender(tx);
+
+        let idiot = || {
+            npc_templates(g.templates())
+                .unwrap()
+                .into_iter()
+                .find(|t| "idiot" == t.info.handle)
+                .unwrap()
+        };
+
+        // the idiot who lives in the corridor, and another wandering the cell
+        let home: PlayerType = idiot().into();
+        let mut namesake = idiot();
+        namesake.loc = Coord(0, 0, 0);
+        let namesake: PlayerType = namesake.into();
+        let h = home.uuid();
+        g.add_player(home);
+        g.add_player(namesake);
+
+        g.kill_player(h).unwrap();
+        for _ in 0..20 {
+            g.tick().unwrap();
+        }
+
+        let spawned: Vec<(String, Coord)> = rx
+            .try_iter()
+            .filter_map(|m| match m {
+                DiscreteMessage::SpawnNpc(p) => Some((p.name(), p.loc())),
+                _ => None,
+            })
+            .filter(|(name, _)| name == "An idiot")
+            .collect();
+        assert_eq!(spawned, vec![("An idiot".to_owned(), Coord(0, 1, 0))]);
+    }
+
+    #[test]
+    fn test_corpse_decay() {
+        let (mut g, u) = game_with_player(&[]);
+        g.set_corpse_decay(2);
+
+        let mut bob = PlayerType::Human(Player::new());
+        bob.set_name("Bob");
+        let x = r#"---
+inner:
+- Holdable:
+    name: pebble
+    display: A shiny pebble lies here
+    description: It's a pebble.
+    handle: [pebble]
+"#;
+        for item in items(x) {
+            bob.insert_item(item).unwrap();
+        }
+        let b = bob.uuid();
+        g.add_player(bob);
+        g.kill_player(b).unwrap();
+
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(msg.to_self().contains("The corpse of Bob lies here"));
+        assert!(!msg.to_self().contains("shiny pebble"));
+
+        g.tick().unwrap();
+        g.tick().unwrap();
+
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(!msg.to_self().contains("corpse of Bob"));
+        assert!(msg.to_self().contains("shiny pebble"));
+    }
+}
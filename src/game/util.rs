@@ -1,4 +1,8 @@
 use super::*;
+use crate::item::template::{ItemTemplate, Templates};
+use crate::player::npc::YamlPlayer;
+use crate::text::chat::ChatChannel;
+use crate::text::social::Social;
 
 fn random_num(x: u8, y: u8) -> u8 {
     rand::thread_rng().gen_range(x, y)
@@ -16,13 +20,58 @@ pub fn random_insult() -> String {
     .to_owned()
 }
 
-pub fn load_rooms(rooms: &mut RoomList) -> GameResult<()> {
+/// The item templates that come with the game, from `items.yaml`
+pub fn item_templates() -> GameResult<Vec<ItemTemplate>> {
+    let bytes = include_bytes!("../../data/items.cbor");
+    Ok(serde_cbor::from_slice(bytes)?)
+}
+
+/// The item templates kept in the database, or the ones that come with the game if it can't be
+/// reached or has none
+pub fn load_templates(db: &Pool) -> GameResult<Templates> {
+    match db.run(|db| db.templates()) {
+        Ok(v) if !v.is_empty() => return Ok(v.into()),
+        Ok(_) => (),
+        Err(e) => eprintln!("unable to load item templates from the database: {}", e),
+    }
+    Ok(item_templates()?.into())
+}
+
+pub fn load_rooms(rooms: &mut RoomList, templates: &Templates) -> GameResult<()> {
     let bytes = include_bytes!("../../data/map.cbor");
     let v: Vec<Room> = serde_cbor::from_slice(bytes)?;
 
     for mut r in v {
-        r.init();
+        r.init(templates)?;
         rooms.insert(r.loc(), r);
     }
     Ok(())
 }
+
+pub fn npc_templates(templates: &Templates) -> GameResult<Vec<YamlPlayer>> {
+    let bytes = include_bytes!("../../data/npc.cbor");
+    let mut v: Vec<YamlPlayer> = serde_cbor::from_slice(bytes)?;
+    for yp in v.iter_mut() {
+        yp.resolve(templates)?;
+    }
+    Ok(v)
+}
+
+pub fn load_npcs(templates: &Templates) -> GameResult<Vec<PlayerType>> {
+    Ok(npc_templates(templates)?
+        .into_iter()
+        .map(|yp| yp.into())
+        .collect())
+}
+
+pub fn load_socials() -> GameResult<Vec<Social>> {
+    let bytes = include_bytes!("../../data/socials.cbor");
+    let mut v: Vec<Social> = serde_cbor::from_slice(bytes)?;
+    v.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(v)
+}
+
+pub fn load_channels() -> GameResult<Vec<ChatChannel>> {
+    let bytes = include_bytes!("../../data/channels.cbor");
+    Ok(serde_cbor::from_slice(bytes)?)
+}
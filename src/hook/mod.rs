@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 #[repr(transparent)]
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct Hook(pub Vec<String>);
 
+#[derive(Clone, Copy)]
 pub struct Grabber<'a> {
     pub handle: &'a str,
     pub index: usize,
@@ -1,16 +1,15 @@
 use std::collections::HashMap;
 
-use crate::attribute::Attribute;
 use crate::error::EnnuiError;
 use crate::game::Game;
-use crate::interpreter::CommandQuality::{Awake, Motion};
+use crate::interpreter::CommandQuality::{Alive, Awake, Peaceful, Standing};
+use crate::player::{PlayerStatus, Role};
 use crate::text::message::{Message, Messenger};
 use std::sync::{Arc, Mutex};
 
 pub type CommandMessage = (Box<dyn Messenger>, Box<dyn Message>);
-pub type CommandFunc = Arc<
-    Mutex<dyn Fn(&mut Game, u128, &[&str]) -> Result<CommandMessage, EnnuiError> + Send + Sync>,
->;
+pub type CommandFunc =
+    Arc<dyn Fn(&mut Game, u128, &[&str]) -> Result<CommandMessage, EnnuiError> + Send + Sync>;
 
 #[derive(Default)]
 pub struct Interpreter {
@@ -23,20 +22,37 @@ pub enum CommandKind {
     South,
     East,
     West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    Up,
+    Down,
     Look,
     Take,
     Drop,
     Give,
+    Put,
     Wear,
+    Wield,
     Remove,
+    Eat,
+    Drink,
+    List,
+    Buy,
+    Sell,
+    Value,
     Chat,
     Say,
     Eval,
     Inventory,
+    Equipment,
     NotFound,
     Ouch,
     Open,
+    Close,
     Unlock,
+    Lock,
     Hit,
     Sleep,
     Stand,
@@ -46,39 +62,107 @@ pub enum CommandKind {
     Souls,
     Combine,
     Quit,
-    // not yet implemented
-    #[allow(dead_code)]
+    Goto,
+    Transfer,
+    Force,
+    Load,
+    Purge,
+    Restore,
+    Shutdown,
+    WizInvis,
+    Silence,
+    Ignore,
+    Emote,
+    Socials,
+    // used when the first word of a command names a social
+    Social,
+    Channels,
+    Subscribe,
+    Mute,
+    // used when the first word of a command names a channel
+    Channel,
     Whisper,
+    Tell,
+    Reply,
     #[allow(dead_code)]
     Blank,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+/// `CommandQuality` describes the state a player must be in before a command will run. The
+/// requirements are checked centrally in `Game::verify_status`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum CommandQuality {
+    Alive,
     Awake,
-    Motion,
+    Standing,
+    Peaceful,
+}
+
+impl CommandQuality {
+    /// All qualities, in the order they are checked. A dead player should hear that they're
+    /// dead before they hear that they're asleep.
+    const ALL: [CommandQuality; 4] = [Alive, Awake, Standing, Peaceful];
+
+    pub fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    /// The player statuses that prevent a command with this quality from running
+    pub fn conflicts(&self) -> &'static [PlayerStatus] {
+        use PlayerStatus::*;
+
+        match self {
+            CommandQuality::Alive => &[Dead],
+            CommandQuality::Awake => &[Asleep],
+            CommandQuality::Standing => &[Asleep, Sitting],
+            CommandQuality::Peaceful => &[Fighting],
+        }
+    }
 }
 
-const NORTH_QUALITIES: [CommandQuality; 2] = [Awake, Motion];
+const MOVE_QUALITIES: [CommandQuality; 3] = [Alive, Awake, Standing];
+const ACTION_QUALITIES: [CommandQuality; 2] = [Alive, Awake];
+const COMBAT_QUALITIES: [CommandQuality; 4] = [Alive, Awake, Standing, Peaceful];
+const CALM_QUALITIES: [CommandQuality; 3] = [Alive, Awake, Peaceful];
+const ALIVE_QUALITIES: [CommandQuality; 1] = [Alive];
 const BLANK_QUALITIES: [CommandQuality; 0] = [];
 
-impl Attribute<CommandQuality> for CommandKind {
-    fn attr(&self) -> Vec<CommandQuality> {
+impl CommandKind {
+    /// The states a player has to be in to use the command
+    pub fn requirements(&self) -> Vec<CommandQuality> {
         use CommandKind::*;
 
         match self {
-            North => &NORTH_QUALITIES[..],
-            _ => &BLANK_QUALITIES[..],
+            North | South | East | West | Up | Down => &MOVE_QUALITIES[..],
+            NorthEast | NorthWest | SouthEast | SouthWest => &MOVE_QUALITIES[..],
+            Look | Take | Drop | Give | Put | Wear | Wield | Remove => &ACTION_QUALITIES[..],
+            Open | Close | Unlock | Lock | Eat | Drink => &ACTION_QUALITIES[..],
+            List | Buy | Sell | Value => &ACTION_QUALITIES[..],
+            Chat | Say | Whisper | Tell | Reply | Stand | Emote | Social => &ACTION_QUALITIES[..],
+            Channel => &ACTION_QUALITIES[..],
+            Hit => &COMBAT_QUALITIES[..],
+            Sleep | Combine => &CALM_QUALITIES[..],
+            Eval | Inventory | Who | Help | Souls | Socials | Wake => &ALIVE_QUALITIES[..],
+            Equipment => &ALIVE_QUALITIES[..],
+            Channels | Subscribe | Mute | Ignore => &ALIVE_QUALITIES[..],
+            NotFound | Ouch => &ALIVE_QUALITIES[..],
+            Goto | Transfer | Force | Load | Purge | Restore | WizInvis | Silence => {
+                &ALIVE_QUALITIES[..]
+            }
+            Quit | Shutdown | Blank => &BLANK_QUALITIES[..],
         }
         .to_vec()
     }
 
-    fn set_attr(&mut self, _: CommandQuality) {
-        unimplemented!()
-    }
+    /// The lowest role that is allowed to use the command
+    pub fn role(&self) -> Role {
+        use CommandKind::*;
 
-    fn unset_attr(&mut self, _: CommandQuality) {
-        unimplemented!()
+        match self {
+            Goto | Load | Purge => Role::Builder,
+            Transfer | Force | Restore | Shutdown | WizInvis | Silence => Role::Admin,
+            _ => Role::Player,
+        }
     }
 }
 
@@ -97,33 +181,85 @@ impl Interpreter {
 
         let s = s.as_ref();
 
+        // privileged commands must be typed out in full, so that nobody shuts down the game by
+        // accident
+        match s.to_lowercase().as_str() {
+            "goto" => return Goto,
+            "transfer" => return Transfer,
+            "force" => return Force,
+            "load" => return Load,
+            "purge" => return Purge,
+            "restore" => return Restore,
+            "shutdown" => return Shutdown,
+            "wizinvis" => return WizInvis,
+            "silence" => return Silence,
+            _ => (),
+        }
+
+        // short forms of directions that would otherwise be read as the start of another command
+        match s.to_lowercase().as_str() {
+            "ne" => return NorthEast,
+            "nw" => return NorthWest,
+            "se" => return SouthEast,
+            "sw" => return SouthWest,
+            "u" => return Up,
+            "d" => return Down,
+            _ => (),
+        }
+
         match s.to_lowercase().as_str() {
             s if s.is_empty() => NotFound,
             s if sw(s, "north") => North,
             s if sw(s, "south") => South,
             s if sw(s, "east") => East,
             s if sw(s, "west") => West,
+            s if sw(s, "northeast") => NorthEast,
+            s if sw(s, "northwest") => NorthWest,
+            s if sw(s, "southeast") => SouthEast,
+            s if sw(s, "southwest") => SouthWest,
+            s if sw(s, "up") => Up,
+            s if sw(s, "down") => Down,
             s if sw(s, "look") => Look,
             s if sw(s, "take") => Take,
             s if sw(s, "get") => Take,
             s if sw(s, "drop") => Drop,
             s if sw(s, "give") => Give,
+            s if sw(s, "put") => Put,
             s if sw(s, "wear") => Wear,
+            s if sw(s, "wield") => Wield,
+            s if sw(s, "eat") => Eat,
+            s if sw(s, "drink") => Drink,
+            s if sw(s, "list") => List,
+            s if sw(s, "buy") => Buy,
+            s if sw(s, "sell") => Sell,
+            s if sw(s, "value") => Value,
             s if sw(s, "chat") => Chat,
             s if sw(s, "say") => Say,
             s if sw(s, "open") => Open,
+            s if sw(s, "close") => Close,
             s if sw(s, "unlock") => Unlock,
+            s if sw(s, "lock") => Lock,
             s if sw(s, "remove") => Remove,
             s if sw(s, "inventory") => Inventory,
+            s if sw(s, "equipment") => Equipment,
             s if sw(s, "evaluate") => Eval,
             s if sw(s, "ouch") => Ouch,
             s if sw(s, "sleep") => Sleep,
             s if sw(s, "stand") => Stand,
             s if sw(s, "wake") => Wake,
             s if sw(s, "who") => Who,
+            s if sw(s, "whisper") => Whisper,
+            s if sw(s, "tell") => Tell,
+            s if sw(s, "reply") => Reply,
             s if sw(s, "help") => Help,
             s if sw(s, "combine") => Combine,
             s if sw(s, "souls") => Souls,
+            s if sw(s, "socials") => Socials,
+            s if sw(s, "emote") => Emote,
+            s if sw(s, "channels") => Channels,
+            s if sw(s, "subscribe") => Subscribe,
+            s if sw(s, "mute") => Mute,
+            s if sw(s, "ignore") => Ignore,
             s if sw(s, "hit") => Hit,
             s if sw(s, "kill") => Hit,
             s if sw(s, "quit") => Quit,
@@ -141,10 +277,15 @@ impl Interpreter {
             + Send
             + Sync,
     {
-        self.commands
-            .lock()
-            .unwrap()
-            .insert(Self::resolve_str(c), b(f));
+        self.insert_kind(Self::resolve_str(c), f);
+    }
+
+    /// Like `insert`, but for commands that can't be reached by name
+    pub fn insert_kind<F: 'static>(&mut self, kind: CommandKind, f: F)
+    where
+        F: Fn(&mut Game, u128, &[&str]) -> Result<CommandMessage, EnnuiError> + Send + Sync,
+    {
+        self.commands.lock().unwrap().insert(kind, b(f));
     }
 
     pub fn commands(&mut self) -> Arc<Mutex<HashMap<CommandKind, CommandFunc>>> {
@@ -164,5 +305,50 @@ fn b<F: 'static>(cf: F) -> CommandFunc
 where
     F: Fn(&mut Game, u128, &[&str]) -> Result<CommandMessage, EnnuiError> + Send + Sync,
 {
-    Arc::new(Mutex::new(cf))
+    Arc::new(cf)
+}
+
+#[cfg(test)]
+mod interpreter_test {
+    use super::*;
+    use crate::game::game_test::game_with_player;
+
+    #[test]
+    fn test_verify_status() {
+        let (mut g, u) = game_with_player(&[PlayerStatus::Asleep]);
+        let (_, msg) = g.interpret(u, "north").unwrap();
+        assert!(msg.to_self().contains("you can't do that while asleep"));
+
+        let (mut g, u) = game_with_player(&[PlayerStatus::Sitting]);
+        let (_, msg) = g.interpret(u, "north").unwrap();
+        let msg = msg.to_self();
+        assert!(msg.contains("you can't do that while sitting down"));
+
+        let (mut g, u) = game_with_player(&[PlayerStatus::Fighting]);
+        let (_, msg) = g.interpret(u, "sleep").unwrap();
+        assert!(msg.to_self().contains("you can't do that while fighting"));
+
+        let (mut g, u) = game_with_player(&[PlayerStatus::Asleep, PlayerStatus::Dead]);
+        let (_, msg) = g.interpret(u, "look").unwrap();
+        assert!(msg.to_self().contains("you can't do that while dead"));
+        assert!(matches!(g.interpret(u, "quit"), Err(EnnuiError::Quit)));
+    }
+
+    #[test]
+    fn test_direction_abbreviations() {
+        assert_eq!(Interpreter::resolve_str("ne"), CommandKind::NorthEast);
+        assert_eq!(Interpreter::resolve_str("southw"), CommandKind::SouthWest);
+        assert_eq!(Interpreter::resolve_str("u"), CommandKind::Up);
+        assert_eq!(Interpreter::resolve_str("d"), CommandKind::Down);
+        assert_eq!(Interpreter::resolve_str("n"), CommandKind::North);
+        assert_eq!(Interpreter::resolve_str("s"), CommandKind::South);
+        assert_eq!(Interpreter::resolve_str("dr"), CommandKind::Drop);
+    }
+
+    #[test]
+    fn test_privileged_commands_need_full_name() {
+        assert_eq!(Interpreter::resolve_str("shutdown"), CommandKind::Shutdown);
+        assert_ne!(Interpreter::resolve_str("shut"), CommandKind::Shutdown);
+        assert_ne!(Interpreter::resolve_str("g"), CommandKind::Goto);
+    }
 }
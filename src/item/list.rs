@@ -1,11 +1,15 @@
-use super::{Describe, Description};
+use super::Describe;
 use crate::error::EnnuiError::Fatal;
 use crate::error::{CmdErr, EnnuiError};
 use crate::hook::{Grabber, Hook};
+use crate::item::timer::Timer;
 use crate::item::YamlItem::{Clothing, Container, Edible, Holdable, Scenery, Weapon};
-use crate::item::{Attribute, DescriptionWithQualities, Item, Quality, YamlItem, YamlItemList};
-use crate::list::{List, ListTrait};
-use crate::obstacle::door::{GuardState, Lock, ObstacleState, StateResult};
+use crate::item::{
+    Attribute, DescriptionWithQualities, Item, ListDescribe, Quality, YamlItem, YamlItemList,
+};
+use crate::list::{List, ListTrait, Stack};
+use crate::location::direction::MapDir;
+use crate::obstacle::door::{GuardState, Lid, Lock, ObstacleState, StateResult};
 use crate::obstacle::key::{Key, KeyType};
 use crate::text::message::MessageFormat;
 use crate::text::Color::Green;
@@ -98,45 +102,319 @@ impl From<YamlItemList> for List<Item, Quality> {
     }
 }
 
-fn conv_desc(d: &mut DescriptionWithQualities, q: Quality) -> Box<dyn super::ItemDescribe> {
-    let new = d.clone();
-    d.set_attr(q);
-    Box::new(new)
+impl From<&List<Item, Quality>> for YamlItemList {
+    fn from(l: &List<Item, Quality>) -> Self {
+        Self {
+            inner: l.list().into_iter().map(YamlItem::from).collect(),
+            info: DescriptionWithQualities::of(l),
+            lid: None,
+            capacity: None,
+        }
+    }
 }
 
-fn conv(list: &mut YamlItemList) -> List<Item, Quality> {
-    let mut ret = List::new();
-    for i in list.inner.iter_mut() {
-        let i = match i {
-            Clothing(i) => Item::Clothing(conv_desc(i, Quality::Clothing)),
-            Weapon(i) => Item::Weapon(conv_desc(i, Quality::Weapon)),
-            Scenery(i) => Item::Scenery(conv_desc(i, Quality::Scenery)),
-            Edible(i) => Item::Edible(conv_desc(i, Quality::Edible)),
-            Holdable(i) => Item::Holdable(conv_desc(i, Quality::Holdable)),
-            Container(ref mut listy) => Item::Container(Box::new(conv(listy))),
+/// Items are kept just as they're written, so that they're saved the same way. Those that don't
+/// say they have their primary attribute have it all the same, by way of `Item::kind`.
+impl From<YamlItem> for Item {
+    fn from(mut i: YamlItem) -> Self {
+        match i {
+            Clothing(i) => Item::Clothing(Box::new(i)),
+            Weapon(i) => Item::Weapon(Box::new(i)),
+            Scenery(i) => Item::Scenery(Box::new(i)),
+            Edible(i) => Item::Edible(Box::new(i)),
+            Holdable(i) => Item::Holdable(Box::new(i)),
+            Container(ref mut listy) => Item::Container(Box::new(Receptacle {
+                items: conv(listy),
+                info: take(&mut listy.info),
+                lid: listy.lid.clone(),
+                capacity: listy.capacity,
+            })),
             YamlItem::Guard {
-                dir, info, lock, ..
+                dir,
+                state,
+                ref mut info,
+                lock,
             } => {
                 let mut g: RenaissanceGuard = take(info).into();
-                g.lock = *lock;
-                g.set_attr(Quality::Container);
-                Item::Guard(*dir, Box::new(g))
+                g.lock = lock;
+                g.state = state;
+                Item::Guard(dir, Box::new(g))
             }
-            YamlItem::Key(n, item) => {
+            YamlItem::Key(n, ref mut item) => {
                 let i = take(item);
                 let mut k: KeyType = i.into();
-                k.set_key(*n);
-                k.add_quality(Quality::Key);
+                k.set_key(n);
                 Item::Key(Box::new(k))
             }
-        };
-        ret.insert_item(i);
+            // references are swapped for their templates when the world is loaded
+            YamlItem::Ref(_) => Item::NoItem,
+        }
+    }
+}
+
+/// Turns a live item back into the form it's saved in, so that the state of the world can be
+/// written out and loaded again just as it was
+impl From<&Item> for YamlItem {
+    fn from(i: &Item) -> Self {
+        match i {
+            Item::Clothing(i) => Clothing(i.to_yaml()),
+            Item::Weapon(i) => Weapon(i.to_yaml()),
+            Item::Scenery(i) => Scenery(i.to_yaml()),
+            Item::Edible(i) => Edible(i.to_yaml()),
+            Item::Holdable(i) => Holdable(i.to_yaml()),
+            Item::Container(i) => Container(i.to_yaml()),
+            Item::Guard(dir, g) => g.to_yaml(*dir),
+            Item::Key(k) => YamlItem::Key(k.key(), k.to_yaml()),
+            // there's nothing to save for an item that isn't there
+            Item::NoItem => YamlItem::default(),
+        }
+    }
+}
+
+fn conv(list: &mut YamlItemList) -> List<Item, Quality> {
+    let mut ret = List::new();
+    for i in take(&mut list.inner) {
+        match i.into() {
+            Item::NoItem => (),
+            i => {
+                ret.insert_item(i);
+            }
+        }
     }
     ret.set_info(list.info.info.clone());
     ret.set_attr_list(list.info.attr.clone());
     ret
 }
 
+impl YamlItemList {
+    /// Puts back anything in the list that's missing from `items`, and refills the containers
+    /// that are still there. Items are counted by name and handle, so stacks are topped up and
+    /// every copy of an item listed more than once comes back. Lids are put back the way the list
+    /// has them. Nothing is taken away.
+    pub fn refill(&self, items: &mut dyn ListTrait<Item = Item>) {
+        let mut containers: Vec<(String, String)> = vec![];
+        let mut counted: Vec<(String, String)> = vec![];
+
+        for def in self.inner.iter() {
+            let name = def.name();
+            let handle = match def.handle().0.first() {
+                Some(h) => h.clone(),
+                None => continue,
+            };
+            let key = (handle.clone(), name.clone());
+            let handle = handle.as_str();
+
+            // the nth container the list has by a name is the nth one of that name in `items`
+            if let Container(list) = def {
+                let nth = containers.iter().filter(|&k| *k == key).count();
+                containers.push(key);
+
+                match matching(items, handle, &name).get(nth) {
+                    Some(&index) => {
+                        if let Some(Item::Container(c)) =
+                            items.get_item_mut(Grabber { handle, index })
+                        {
+                            if let Some(lid) = c.lid_mut() {
+                                *lid = Lid::default();
+                            }
+                            list.refill(c.as_list_mut());
+                            if let (Some(lid), Some(def_lid)) = (c.lid_mut(), &list.lid) {
+                                *lid = def_lid.clone();
+                            }
+                        }
+                    }
+                    None => {
+                        items.insert_item(def.clone().into()).ok();
+                    }
+                }
+                continue;
+            }
+
+            // anything else is counted all at once, the first time its name comes up
+            if counted.contains(&key) {
+                continue;
+            }
+            counted.push(key);
+
+            let defs: Vec<Item> = self
+                .inner
+                .iter()
+                .filter(|d| !matches!(d, Container(_)))
+                .filter(|d| {
+                    d.name() == name && d.handle().0.first().map(String::as_str) == Some(handle)
+                })
+                .map(|d| d.clone().into())
+                .collect();
+            let wanted: u32 = defs.iter().map(|i| i.count()).sum();
+            let have: u32 = matching(items, handle, &name)
+                .into_iter()
+                .filter_map(|index| items.get_item(Grabber { handle, index }))
+                .map(|i| i.count())
+                .sum();
+
+            let mut missing = wanted.saturating_sub(have);
+            for mut item in defs {
+                if missing == 0 {
+                    break;
+                }
+                if item.count() > missing {
+                    item = match item.split(missing) {
+                        Some(part) => part,
+                        None => continue,
+                    };
+                }
+                missing -= item.count();
+                items.insert_item(item).ok();
+            }
+        }
+    }
+}
+
+/// Where the items in the list with the given handle and name are, as indices among the items
+/// with that handle
+fn matching(items: &dyn ListTrait<Item = Item>, handle: &str, name: &str) -> Vec<usize> {
+    items
+        .list()
+        .into_iter()
+        .filter(|i| i.handle() == handle)
+        .enumerate()
+        .filter(|(_, i)| i.name() == name)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// `Receptacle` is a container loaded from the map, or left behind by the dead. It may have a lid,
+/// a limited capacity, and a weight and timer of its own in its `info`. Nothing goes in or comes
+/// out while the lid is closed, and nothing more goes in once it's full.
+#[derive(Debug, Default)]
+pub struct Receptacle {
+    pub items: List<Item, Quality>,
+    pub info: DescriptionWithQualities,
+    pub lid: Option<Lid>,
+    pub capacity: Option<usize>,
+}
+
+impl Receptacle {
+    fn is_closed(&self) -> bool {
+        self.lid.as_ref().map_or(false, |l| !l.is_open())
+    }
+}
+
+impl ListDescribe for Receptacle {
+    fn lid(&self) -> Option<&Lid> {
+        self.lid.as_ref()
+    }
+
+    fn lid_mut(&mut self) -> Option<&mut Lid> {
+        self.lid.as_mut()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    fn weight(&self) -> u32 {
+        self.info.weight + self.items.list().iter().map(|i| i.weight()).sum::<u32>()
+    }
+
+    fn timer(&self) -> Option<&Timer> {
+        self.info.timer.as_ref()
+    }
+
+    fn timer_mut(&mut self) -> Option<&mut Timer> {
+        self.info.timer.as_mut()
+    }
+
+    fn as_list_mut(&mut self) -> &mut dyn ListTrait<Item = Item> {
+        self
+    }
+
+    fn contents_mut(&mut self) -> &mut List<Item, Quality> {
+        &mut self.items
+    }
+
+    fn into_items(self: Box<Self>) -> Vec<Item> {
+        self.items.into_inner()
+    }
+
+    fn to_yaml(&self) -> YamlItemList {
+        YamlItemList {
+            inner: self.items.list().into_iter().map(YamlItem::from).collect(),
+            info: self.info.clone(),
+            lid: self.lid.clone(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl ListTrait for Receptacle {
+    type Item = Item;
+
+    fn get_item(&self, handle: Grabber) -> Option<&Item> {
+        self.items.get_item(handle)
+    }
+
+    fn get_item_mut(&mut self, handle: Grabber) -> Option<&mut Item> {
+        self.items.get_item_mut(handle)
+    }
+
+    fn get_item_owned(&mut self, handle: Grabber) -> Result<Item, EnnuiError> {
+        if self.is_closed() {
+            return Err(EnnuiError::Simple(CmdErr::ItemNotFound));
+        }
+
+        self.items.get_item_owned(handle)
+    }
+
+    fn insert_item(&mut self, item: Item) -> Result<(), Item> {
+        if self.is_closed() || self.is_full() {
+            return Err(item);
+        }
+
+        self.items.insert_item(item)
+    }
+
+    fn display_items(&self) -> String {
+        self.items.display_items()
+    }
+
+    fn list(&self) -> Vec<&Self::Item> {
+        self.items.list()
+    }
+}
+
+impl Describe for Receptacle {
+    fn name(&self) -> String {
+        self.info.name()
+    }
+
+    fn display(&self) -> String {
+        self.info.display()
+    }
+
+    fn description(&self) -> String {
+        self.info.description()
+    }
+
+    fn handle(&self) -> Hook {
+        self.info.handle()
+    }
+}
+
+impl Attribute<Quality> for Receptacle {
+    fn attr(&self) -> Vec<Quality> {
+        self.info.attr()
+    }
+
+    fn set_attr(&mut self, q: Quality) {
+        self.info.set_attr(q)
+    }
+
+    fn unset_attr(&mut self, q: Quality) {
+        self.info.unset_attr(q)
+    }
+}
+
 pub trait Guard: Lock<GuardState> + ListTrait<Item = Item> {}
 impl Guard for RenaissanceGuard {}
 
@@ -181,12 +459,20 @@ pub struct RenaissanceGuard {
     #[serde(default)]
     pub state: GuardState,
     pub lock: u64,
-    pub info: Description,
-    #[serde(default)]
-    pub attr: Vec<Quality>,
+    #[serde(flatten)]
+    pub info: DescriptionWithQualities,
 }
 
-impl crate::item::GuardDescribe for RenaissanceGuard {}
+impl crate::item::GuardDescribe for RenaissanceGuard {
+    fn to_yaml(&self, dir: MapDir) -> YamlItem {
+        YamlItem::Guard {
+            dir,
+            state: self.state,
+            info: self.info.clone(),
+            lock: self.lock,
+        }
+    }
+}
 
 impl Clone for RenaissanceGuard {
     fn clone(&self) -> Self {
@@ -195,7 +481,6 @@ impl Clone for RenaissanceGuard {
             items: List::new(),
             state: self.state,
             lock: self.lock,
-            attr: self.attr.clone(),
         }
     }
 }
@@ -227,26 +512,15 @@ impl Describe for RenaissanceGuard {
 
 impl Attribute<Quality> for RenaissanceGuard {
     fn attr(&self) -> Vec<Quality> {
-        self.attr.clone()
+        self.info.attr()
     }
 
     fn set_attr(&mut self, q: Quality) {
-        self.attr.push(q);
+        self.info.set_attr(q)
     }
 
     fn unset_attr(&mut self, q: Quality) {
-        let mut index = 0;
-        for qual in self.attr.iter() {
-            if *qual == q {
-                break;
-            }
-
-            index += 1;
-        }
-
-        if index < self.attr.len() {
-            self.attr.remove(index);
-        }
+        self.info.unset_attr(q)
     }
 }
 
@@ -284,11 +558,245 @@ impl Lock<GuardState> for RenaissanceGuard {
 }
 
 impl From<DescriptionWithQualities> for RenaissanceGuard {
-    fn from(b: DescriptionWithQualities) -> Self {
+    fn from(info: DescriptionWithQualities) -> Self {
         Self {
-            info: b.info,
-            attr: b.attr,
+            info,
             ..Self::default()
         }
     }
 }
+
+#[cfg(test)]
+mod item_list_test {
+    use super::*;
+    use crate::item::weapon::{DamageType, Dice, WeaponStats};
+    use crate::item::Slot;
+    use crate::obstacle::door::DoorState;
+    use crate::player::effect::{Effect, Food, Stat};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn word(rng: &mut StdRng) -> String {
+        let words = [
+            "rusty", "sword", "old", "sack", "gold", "coin", "oak", "chest", "it's",
+        ];
+        (0..rng.gen_range(1, 4))
+            .map(|_| words[rng.gen_range(0, words.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn maybe<T>(rng: &mut StdRng, f: impl FnOnce(&mut StdRng) -> T) -> Option<T> {
+        if rng.gen() {
+            Some(f(rng))
+        } else {
+            None
+        }
+    }
+
+    fn random_description(rng: &mut StdRng, primary: Quality) -> DescriptionWithQualities {
+        // names are kept apart, so that stackable items don't merge
+        let name = format!("{} {}", word(rng), rng.gen::<u32>());
+        let mut d = DescriptionWithQualities::new(&name, Some(&word(rng)), Hook::default());
+        d.info.display = word(rng);
+        d.info.handle = Hook((0..rng.gen_range(1, 3)).map(|_| word(rng)).collect());
+        // the primary attribute is often left for the item's kind to imply
+        if rng.gen() {
+            d.attr.push(primary);
+        }
+        if rng.gen() {
+            d.attr.push(Quality::Stackable);
+            d.count = rng.gen_range(1, 50);
+        }
+        d.weight = rng.gen_range(0, 20);
+        d.price = rng.gen_range(0, 100);
+        d.slot = maybe(rng, |_| Slot::Feet);
+        d.timer = maybe(rng, |rng| {
+            Timer::new(rng.gen_range(1, 30), maybe(rng, word))
+        });
+        d.weapon = maybe(rng, |rng| WeaponStats {
+            damage: Dice {
+                count: rng.gen_range(1, 4),
+                sides: rng.gen_range(2, 12),
+                bonus: rng.gen_range(-2, 3),
+            },
+            kind: DamageType::Piercing,
+            speed: rng.gen_range(1, 3),
+        });
+        d.food = maybe(rng, |rng| Food {
+            drink: rng.gen(),
+            fills: rng.gen_range(0, 50),
+            effects: vec![
+                Effect::Restore {
+                    stat: Stat::Hit,
+                    amount: rng.gen_range(1, 10),
+                },
+                Effect::Poison {
+                    amount: rng.gen_range(1, 10),
+                    ticks: rng.gen_range(1, 10),
+                },
+            ],
+        });
+        d
+    }
+
+    /// Any item at all, as it might be written in the YAML, with containers nested up to `depth`
+    fn random_item(rng: &mut StdRng, depth: u32) -> YamlItem {
+        match rng.gen_range(0, 8) {
+            0 => Clothing(random_description(rng, Quality::Clothing)),
+            1 => Weapon(random_description(rng, Quality::Weapon)),
+            2 => Scenery(random_description(rng, Quality::Scenery)),
+            3 => Edible(random_description(rng, Quality::Edible)),
+            4 => YamlItem::Key(rng.gen(), random_description(rng, Quality::Key)),
+            5 => YamlItem::Guard {
+                dir: MapDir::East,
+                state: if rng.gen() {
+                    GuardState::Open
+                } else {
+                    GuardState::Closed
+                },
+                info: random_description(rng, Quality::Container),
+                lock: rng.gen(),
+            },
+            6 if depth > 0 => {
+                let mut list: YamlItemList = (0..rng.gen_range(0, 4))
+                    .map(|_| random_item(rng, depth - 1))
+                    .collect::<Vec<_>>()
+                    .into();
+                list.info = random_description(rng, Quality::Container);
+                list.capacity = maybe(rng, |rng| rng.gen_range(4, 10));
+                list.lid = maybe(rng, |rng| {
+                    let states = [
+                        DoorState::Open,
+                        DoorState::Closed,
+                        DoorState::Locked,
+                        DoorState::MagicallySealed,
+                        DoorState::PermaLocked,
+                        DoorState::Guarded(word(rng)),
+                        DoorState::None,
+                    ];
+                    let state = &states[rng.gen_range(0, states.len())];
+                    let mut lid = serde_yaml::Mapping::new();
+                    lid.insert("state".into(), serde_yaml::to_value(state).unwrap());
+                    if let Some(keyhole) = maybe(rng, |rng| rng.gen::<u32>()) {
+                        lid.insert("keyhole".into(), keyhole.into());
+                    }
+                    serde_yaml::from_value(serde_yaml::Value::Mapping(lid)).unwrap()
+                });
+                Container(list)
+            }
+            _ => Holdable(random_description(rng, Quality::Holdable)),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let before = random_item(&mut rng, 3);
+            let item: Item = before.clone().into();
+            let after = YamlItem::from(&item);
+
+            let before = serde_yaml::to_string(&before).unwrap();
+            let after = serde_yaml::to_string(&after).unwrap();
+            assert_eq!(before, after);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_list() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..100 {
+            let items: Vec<_> = (0..rng.gen_range(0, 6))
+                .map(|_| random_item(&mut rng, 2))
+                .collect();
+            let before: YamlItemList = items.into();
+            let list: List<Item, Quality> = before.clone().into();
+            let after = YamlItemList::from(&list);
+
+            let before = serde_yaml::to_string(&before).unwrap();
+            let after = serde_yaml::to_string(&after).unwrap();
+            assert_eq!(before, after);
+        }
+    }
+
+    #[test]
+    fn test_closed_chest() {
+        let x = r#"---
+inner:
+- Container:
+    info:
+      name: chest
+      display: A sturdy oak chest sits here
+      description: It's banded with iron.
+      handle: [chest]
+    lid:
+      state: Closed
+    inner:
+    - Holdable:
+        name: coin
+        display: A gold coin glints here
+        description: It's a coin.
+        handle: [coin]
+"#;
+        let yaml: YamlItemList = serde_yaml::from_str(x).unwrap();
+        let mut list: List<Item, Quality> = yaml.into();
+
+        let chest = match list.get_item_mut(Grabber::from_str("chest")) {
+            Some(Item::Container(c)) => c,
+            _ => panic!("chest should be a container"),
+        };
+        assert!(!chest.lid().unwrap().is_open());
+        assert!(chest.get_item_owned(Grabber::from_str("coin")).is_err());
+
+        let lid = chest.lid_mut().unwrap();
+        assert!(lid.unlock(DoorState::Open, None).is_ok());
+        assert!(chest.get_item_owned(Grabber::from_str("coin")).is_ok());
+    }
+
+    #[test]
+    fn test_refill_counts() {
+        let x = r#"---
+inner:
+- Holdable:
+    name: coin
+    display: A pile of coins glitters here
+    description: They're gold coins.
+    handle: [coin]
+    attr: [Stackable]
+    count: 10
+- Holdable:
+    name: rock
+    display: A rock sits here
+    description: It's a rock.
+    handle: [rock]
+- Holdable:
+    name: rock
+    display: A rock sits here
+    description: It's a rock.
+    handle: [rock]
+"#;
+        let def: YamlItemList = serde_yaml::from_str(x).unwrap();
+        let mut items: List<Item, Quality> = def.clone().into();
+
+        items.get_some_owned("coin".into(), 4).unwrap();
+        items.get_item_owned("rock".into()).unwrap();
+        items.get_item_owned("rock".into()).unwrap();
+        def.refill(&mut items);
+
+        let count = |items: &List<Item, Quality>, handle: &str| -> u32 {
+            items
+                .list()
+                .into_iter()
+                .filter(|i| i.handle() == handle)
+                .map(|i| i.count())
+                .sum()
+        };
+        assert_eq!(count(&items, "coin"), 10);
+        assert_eq!(count(&items, "rock"), 2);
+
+        def.refill(&mut items);
+        assert_eq!(count(&items, "coin"), 10);
+        assert_eq!(count(&items, "rock"), 2);
+    }
+}
@@ -4,20 +4,133 @@ use std::fmt::Debug;
 use crate::attribute::{Attribute, Quality};
 use crate::describe::{Describe, Description};
 use crate::hook::Hook;
-use crate::list::{List, ListTrait};
+use crate::list::{List, ListTrait, Stack};
 use crate::location::direction::MapDir;
-use crate::obstacle::door::GuardState;
+use crate::obstacle::door::{GuardState, Lid};
 use crate::obstacle::key::{Key, KeyType};
+use crate::player::effect::Food;
 use list::{Guard, ItemList};
+use timer::Timer;
+use weapon::WeaponStats;
 use Item::NoItem;
 
 pub mod list;
+pub mod template;
+pub mod timer;
+pub mod weapon;
 
-pub trait ItemDescribe: Describe + Attribute<Quality> {}
-pub trait ListDescribe: Describe + Attribute<Quality> + ListTrait<Item = Item> {}
-pub trait GuardDescribe: Describe + Attribute<Quality> + Guard {}
+pub trait ItemDescribe: Describe + Attribute<Quality> {
+    fn weight(&self) -> u32 {
+        0
+    }
+
+    /// Where the item is worn, if it says
+    fn slot(&self) -> Option<Slot> {
+        None
+    }
+
+    fn weapon(&self) -> Option<&WeaponStats> {
+        None
+    }
+
+    fn food(&self) -> Option<&Food> {
+        None
+    }
+
+    fn price(&self) -> u64 {
+        0
+    }
 
-impl ListDescribe for List<Item, Quality> {}
+    /// How long the item has left, if it doesn't last forever
+    fn timer(&self) -> Option<&Timer> {
+        None
+    }
+
+    fn timer_mut(&mut self) -> Option<&mut Timer> {
+        None
+    }
+
+    /// How many of the item there are, for items that stack
+    fn count(&self) -> u32 {
+        1
+    }
+
+    fn set_count(&mut self, _n: u32) {}
+
+    /// A copy of the item, `n` strong, for splitting off a stack
+    fn with_count(&self, _n: u32) -> Option<Box<dyn ItemDescribe>> {
+        None
+    }
+
+    /// Everything about the item, in the form it's saved in
+    fn to_yaml(&self) -> DescriptionWithQualities;
+}
+
+pub trait ListDescribe: Describe + Attribute<Quality> + ListTrait<Item = Item> {
+    /// The weight of the container along with everything in it
+    fn weight(&self) -> u32 {
+        self.list().iter().map(|i| i.weight()).sum()
+    }
+
+    /// The lid of a container that can be closed, if it has one
+    fn lid(&self) -> Option<&Lid> {
+        None
+    }
+
+    fn lid_mut(&mut self) -> Option<&mut Lid> {
+        None
+    }
+
+    /// How many items the container can hold, if there's a limit
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn is_full(&self) -> bool {
+        self.capacity().map_or(false, |c| self.list().len() >= c)
+    }
+
+    fn timer(&self) -> Option<&Timer> {
+        None
+    }
+
+    fn timer_mut(&mut self) -> Option<&mut Timer> {
+        None
+    }
+
+    fn as_list_mut(&mut self) -> &mut dyn ListTrait<Item = Item>;
+
+    /// Everything in the container, to be worked on in place
+    fn contents_mut(&mut self) -> &mut List<Item, Quality>;
+
+    /// Empties the container for good, giving back everything that was in it
+    fn into_items(self: Box<Self>) -> Vec<Item>;
+
+    /// The container and everything in it, in the form it's saved in
+    fn to_yaml(&self) -> YamlItemList;
+}
+pub trait GuardDescribe: Describe + Attribute<Quality> + Guard {
+    /// The guard, standing in the given direction, in the form it's saved in
+    fn to_yaml(&self, dir: MapDir) -> YamlItem;
+}
+
+impl ListDescribe for List<Item, Quality> {
+    fn as_list_mut(&mut self) -> &mut dyn ListTrait<Item = Item> {
+        self
+    }
+
+    fn contents_mut(&mut self) -> &mut List<Item, Quality> {
+        self
+    }
+
+    fn into_items(self: Box<Self>) -> Vec<Item> {
+        self.into_inner()
+    }
+
+    fn to_yaml(&self) -> YamlItemList {
+        self.into()
+    }
+}
 
 /// YamlItem is a no-frills representation of various objects, wrapped in a primary attribute.
 /// Its primary use is for serialization
@@ -36,6 +149,8 @@ pub enum YamlItem {
     },
     Container(YamlItemList),
     Key(u64, DescriptionWithQualities),
+    /// A copy of the item template with the given id
+    Ref(u64),
 }
 
 /// Item is a simple wrapping of an item-y type in a primary attribute
@@ -58,6 +173,163 @@ impl Default for Item {
     }
 }
 
+impl Item {
+    /// The primary attribute of the item's kind, which every item of that kind has whether or not
+    /// it says so
+    pub fn kind(&self) -> Option<Quality> {
+        use Item::*;
+        match self {
+            Clothing(_) => Some(Quality::Clothing),
+            Weapon(_) => Some(Quality::Weapon),
+            Scenery(_) => Some(Quality::Scenery),
+            Edible(_) => Some(Quality::Edible),
+            Holdable(_) => Some(Quality::Holdable),
+            Container(_) | Guard(..) => Some(Quality::Container),
+            Key(_) => Some(Quality::Key),
+            NoItem => None,
+        }
+    }
+
+    /// Where the item goes when it's worn. Clothing that doesn't say is worn on the body.
+    pub fn slot(&self) -> Option<Slot> {
+        use Item::*;
+        let declared = match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.slot(),
+            _ => None,
+        };
+
+        if self.is(Quality::Clothing) {
+            Some(declared.unwrap_or_default())
+        } else if self.is(Quality::Weapon) {
+            Some(Slot::Held)
+        } else {
+            declared
+        }
+    }
+
+    /// How the item fares in a fight, if it's a weapon
+    pub fn weapon(&self) -> Option<&WeaponStats> {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.weapon(),
+            _ => None,
+        }
+    }
+
+    /// What eating or drinking the item does, if it's edible
+    pub fn food(&self) -> Option<&Food> {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.food(),
+            _ => None,
+        }
+    }
+
+    /// What the item is worth in gold, before any shop takes its cut
+    pub fn price(&self) -> u64 {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.price(),
+            Key(i) => i.price(),
+            _ => 0,
+        }
+    }
+
+    pub fn timer(&self) -> Option<&Timer> {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.timer(),
+            Container(i) => i.timer(),
+            Key(i) => i.timer(),
+            _ => None,
+        }
+    }
+
+    pub fn timer_mut(&mut self) -> Option<&mut Timer> {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.timer_mut(),
+            Container(i) => i.timer_mut(),
+            Key(i) => i.timer_mut(),
+            _ => None,
+        }
+    }
+
+    pub fn weight(&self) -> u32 {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.weight(),
+            Container(i) => i.weight(),
+            Key(i) => i.weight(),
+            Guard(..) | NoItem => 0,
+        }
+    }
+}
+
+impl Stack for Item {
+    fn count(&self) -> u32 {
+        use Item::*;
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.count(),
+            _ => 1,
+        }
+    }
+
+    /// Items only stack with their own kind, only if they're marked `Stackable`, and only with
+    /// items that are the same in every way but how many there are
+    fn merge(&mut self, other: Self) -> Result<(), Self> {
+        use Item::*;
+        let same = |i: &dyn ItemDescribe| DescriptionWithQualities {
+            count: 1,
+            ..i.to_yaml()
+        };
+        let stacks = self.is(Quality::Stackable)
+            && match (&*self, &other) {
+                (Clothing(a), Clothing(b))
+                | (Weapon(a), Weapon(b))
+                | (Scenery(a), Scenery(b))
+                | (Edible(a), Edible(b))
+                | (Holdable(a), Holdable(b)) => same(a.as_ref()) == same(b.as_ref()),
+                _ => false,
+            };
+        if !stacks {
+            return Err(other);
+        }
+
+        let count = self.count() + other.count();
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.set_count(count),
+            _ => return Err(other),
+        }
+        Ok(())
+    }
+
+    fn split(&mut self, n: u32) -> Option<Self> {
+        use Item::*;
+        if n == 0 || n >= self.count() {
+            return None;
+        }
+
+        let rest = self.count() - n;
+        let kind: fn(Box<dyn ItemDescribe>) -> Item = match self {
+            Clothing(_) => Clothing,
+            Weapon(_) => Weapon,
+            Scenery(_) => Scenery,
+            Edible(_) => Edible,
+            Holdable(_) => Holdable,
+            _ => return None,
+        };
+        match self {
+            Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => {
+                let part = i.with_count(n)?;
+                i.set_count(rest);
+                Some(kind(part))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl Describe for Item {
     fn name(&self) -> String {
         use Item::*;
@@ -107,13 +379,19 @@ impl Describe for Item {
 impl Attribute<Quality> for Item {
     fn attr(&self) -> Vec<Quality> {
         use Item::*;
-        match self {
+        let mut attr = match self {
             Clothing(i) | Weapon(i) | Scenery(i) | Edible(i) | Holdable(i) => i.attr(),
             Container(i) => i.attr(),
             Key(i) => i.attr(),
             Guard(_, i) => i.attr(),
             NoItem => vec![],
+        };
+        if let Some(q) = self.kind() {
+            if !attr.contains(&q) {
+                attr.push(q);
+            }
         }
+        attr
     }
 
     fn set_attr(&mut self, q: Quality) {
@@ -145,15 +423,116 @@ impl Default for YamlItem {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 pub struct DescriptionWithQualities {
     #[serde(flatten)]
     pub info: Description,
     #[serde(default)]
     pub attr: Vec<Quality>,
+    #[serde(default)]
+    pub weight: u32,
+    #[serde(default)]
+    pub slot: Option<Slot>,
+    #[serde(default)]
+    pub weapon: Option<WeaponStats>,
+    #[serde(default)]
+    pub food: Option<Food>,
+    #[serde(default)]
+    pub price: u64,
+    #[serde(default)]
+    pub timer: Option<Timer>,
+    #[serde(default)]
+    pub count: u32,
 }
 
-impl ItemDescribe for DescriptionWithQualities {}
+impl ItemDescribe for DescriptionWithQualities {
+    /// The weight of the whole stack. `weight` itself is for just one.
+    fn weight(&self) -> u32 {
+        self.weight * self.count()
+    }
+
+    fn slot(&self) -> Option<Slot> {
+        self.slot
+    }
+
+    fn weapon(&self) -> Option<&WeaponStats> {
+        self.weapon.as_ref()
+    }
+
+    fn food(&self) -> Option<&Food> {
+        self.food.as_ref()
+    }
+
+    fn price(&self) -> u64 {
+        self.price
+    }
+
+    fn timer(&self) -> Option<&Timer> {
+        self.timer.as_ref()
+    }
+
+    fn timer_mut(&mut self) -> Option<&mut Timer> {
+        self.timer.as_mut()
+    }
+
+    /// Anything that doesn't say how many there are is just the one
+    fn count(&self) -> u32 {
+        self.count.max(1)
+    }
+
+    fn set_count(&mut self, n: u32) {
+        self.count = n;
+    }
+
+    fn with_count(&self, n: u32) -> Option<Box<dyn ItemDescribe>> {
+        let mut part = self.clone();
+        part.count = n;
+        Some(Box::new(part))
+    }
+    fn to_yaml(&self) -> DescriptionWithQualities {
+        self.clone()
+    }
+}
+
+/// `Slot` is a place on the body where clothing is worn. Only one item fits in each slot.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Slot {
+    Head,
+    Neck,
+    Body,
+    Arms,
+    Hands,
+    Waist,
+    Legs,
+    Feet,
+    Held,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 9] = [
+        Slot::Head,
+        Slot::Neck,
+        Slot::Body,
+        Slot::Arms,
+        Slot::Hands,
+        Slot::Waist,
+        Slot::Legs,
+        Slot::Feet,
+        Slot::Held,
+    ];
+}
+
+impl Default for Slot {
+    fn default() -> Self {
+        Slot::Body
+    }
+}
+
+impl std::fmt::Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
 
 impl Describe for DescriptionWithQualities {
     fn name(&self) -> String {
@@ -191,6 +570,20 @@ impl Attribute<Quality> for DescriptionWithQualities {
 }
 
 impl DescriptionWithQualities {
+    /// The description and qualities of anything that has them, and nothing more
+    pub fn of<T: Describe + Attribute<Quality> + ?Sized>(d: &T) -> Self {
+        Self {
+            info: Description {
+                name: d.name(),
+                display: d.display(),
+                description: d.description(),
+                handle: d.handle(),
+            },
+            attr: d.attr(),
+            ..Self::default()
+        }
+    }
+
     pub fn new(name: &str, description: Option<&str>, handle: Hook) -> Self {
         let description = description.unwrap_or_default().to_owned();
         let name = name.to_owned();
@@ -205,6 +598,13 @@ impl DescriptionWithQualities {
                 display,
             },
             attr: attributes,
+            weight: 0,
+            slot: None,
+            weapon: None,
+            food: None,
+            price: 0,
+            timer: None,
+            count: 1,
         }
     }
 }
@@ -215,6 +615,19 @@ pub struct YamlItemList {
     inner: Vec<YamlItem>,
     #[serde(default)]
     info: DescriptionWithQualities,
+    #[serde(default)]
+    lid: Option<Lid>,
+    #[serde(default)]
+    capacity: Option<usize>,
+}
+
+impl From<Vec<YamlItem>> for YamlItemList {
+    fn from(inner: Vec<YamlItem>) -> Self {
+        Self {
+            inner,
+            ..Self::default()
+        }
+    }
 }
 
 impl Attribute<Quality> for YamlItemList {
@@ -272,7 +685,16 @@ impl YamlItemList {
                     handle: crate::handle![],
                 },
                 attr: vec![Quality::Container],
+                weight: 0,
+                slot: None,
+                weapon: None,
+                food: None,
+                price: 0,
+                timer: None,
+                count: 1,
             },
+            lid: None,
+            capacity: None,
         }
     }
     pub fn get(&self, handle: &str) -> Option<&YamlItem> {
@@ -288,6 +710,24 @@ impl YamlItemList {
     }
 }
 
+/// What an unresolved reference looks like, which is to say nothing at all
+static UNRESOLVED: DescriptionWithQualities = DescriptionWithQualities {
+    info: Description {
+        name: String::new(),
+        display: String::new(),
+        description: String::new(),
+        handle: Hook(Vec::new()),
+    },
+    attr: Vec::new(),
+    weight: 0,
+    slot: None,
+    weapon: None,
+    food: None,
+    price: 0,
+    timer: None,
+    count: 1,
+};
+
 impl YamlItem {
     fn safe_unwrap(&self) -> &DescriptionWithQualities {
         match self {
@@ -299,6 +739,7 @@ impl YamlItem {
             | YamlItem::Edible(item) => item,
             YamlItem::Container(i) => &i.info,
             YamlItem::Guard { info, .. } => &info,
+            YamlItem::Ref(_) => &UNRESOLVED,
         }
     }
 }
@@ -323,35 +764,20 @@ impl Describe for YamlItem {
 
 impl Attribute<Quality> for KeyType {
     fn attr(&self) -> Vec<Quality> {
-        self.attr.clone()
+        self.info.attr()
     }
 
     fn set_attr(&mut self, q: Quality) {
-        self.attr.push(q);
+        self.info.set_attr(q)
     }
 
     fn unset_attr(&mut self, q: Quality) {
-        let mut index = 0;
-        for qual in self.attr.iter() {
-            if *qual == q {
-                break;
-            }
-
-            index += 1;
-        }
-
-        if index < self.attr.len() {
-            self.attr.remove(index);
-        }
+        self.info.unset_attr(q)
     }
 }
 
 impl From<DescriptionWithQualities> for KeyType {
-    fn from(b: DescriptionWithQualities) -> Self {
-        Self {
-            info: b.info,
-            attr: b.attr,
-            key: 0,
-        }
+    fn from(info: DescriptionWithQualities) -> Self {
+        Self { info, key: 0 }
     }
 }
@@ -0,0 +1,147 @@
+use super::{Item, YamlItem, YamlItemList};
+use crate::error::EnnuiError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How deeply templates may refer to other templates, so that one which refers to itself can't
+/// go on forever
+const MAX_DEPTH: usize = 16;
+
+/// `ItemTemplate` is an item written out once, in `items.yaml`, and referred to everywhere else
+/// by its id, as in `- Ref: 3`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemTemplate {
+    pub id: u64,
+    pub item: YamlItem,
+}
+
+/// `Templates` holds every item template, keyed by id
+#[derive(Debug, Default)]
+pub struct Templates(HashMap<u64, YamlItem>);
+
+impl From<Vec<ItemTemplate>> for Templates {
+    fn from(v: Vec<ItemTemplate>) -> Self {
+        Self(v.into_iter().map(|t| (t.id, t.item)).collect())
+    }
+}
+
+impl Templates {
+    pub fn get(&self, id: u64) -> Option<&YamlItem> {
+        self.0.get(&id)
+    }
+
+    /// Makes a new item from the template with the given id
+    pub fn spawn(&self, id: u64) -> Result<Item, EnnuiError> {
+        let mut item = YamlItem::Ref(id);
+        self.resolve_item(&mut item, 0)?;
+        Ok(item.into())
+    }
+
+    /// Swaps every reference in the list, however deep, for a copy of its template
+    pub fn resolve(&self, list: &mut YamlItemList) -> Result<(), EnnuiError> {
+        self.resolve_all(&mut list.inner)
+    }
+
+    /// Swaps every reference among the items, however deep, for a copy of its template
+    pub fn resolve_all(&self, items: &mut [YamlItem]) -> Result<(), EnnuiError> {
+        self.resolve_items(items, 0)
+    }
+
+    fn resolve_items(&self, items: &mut [YamlItem], depth: usize) -> Result<(), EnnuiError> {
+        for item in items.iter_mut() {
+            self.resolve_item(item, depth)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_item(&self, item: &mut YamlItem, depth: usize) -> Result<(), EnnuiError> {
+        if depth > MAX_DEPTH {
+            return Err(EnnuiError::Fatal(
+                "item templates refer to each other too deeply".to_owned(),
+            ));
+        }
+
+        if let YamlItem::Ref(id) = item {
+            let id = *id;
+            *item = self
+                .get(id)
+                .cloned()
+                .ok_or_else(|| EnnuiError::Fatal(format!("no item template with id {}", id)))?;
+            return self.resolve_item(item, depth + 1);
+        }
+
+        if let YamlItem::Container(list) = item {
+            self.resolve_items(&mut list.inner, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod template_test {
+    use super::*;
+    use crate::attribute::{Attribute, Quality};
+    use crate::describe::Describe;
+    use crate::list::{List, ListTrait};
+
+    fn templates() -> Templates {
+        let x = r#"---
+- id: 1
+  item:
+    Weapon:
+      name: rusty sword
+      display: A rusty sword is here
+      description: An old rusted sword.
+      handle: [sword, rusty]
+      weight: 8
+- id: 2
+  item:
+    Container:
+      info:
+        name: sack
+        display: A sack lies here
+        description: It's a sack.
+        handle: [sack]
+      inner:
+      - Ref: 1
+- id: 3
+  item:
+    Ref: 3
+"#;
+        let v: Vec<ItemTemplate> = serde_yaml::from_str(x).unwrap();
+        v.into()
+    }
+
+    #[test]
+    fn test_spawn() {
+        let t = templates();
+
+        let sword = t.spawn(1).unwrap();
+        assert_eq!(sword.name(), "rusty sword");
+        assert!(sword.is(Quality::Weapon));
+        assert_eq!(sword.weight(), 8);
+
+        let sack = t.spawn(2).unwrap();
+        assert!(matches!(sack, Item::Container(_)));
+        assert_eq!(sack.weight(), 8);
+
+        assert!(t.spawn(3).is_err());
+        assert!(t.spawn(4).is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let t = templates();
+        let x = r#"---
+inner:
+- Ref: 1
+- Ref: 2
+"#;
+        let mut list: YamlItemList = serde_yaml::from_str(x).unwrap();
+        t.resolve(&mut list).unwrap();
+        let list: List<Item, Quality> = list.into();
+        assert!(list.get_item("sword".into()).is_some());
+        assert!(list.get_item("sack".into()).is_some());
+    }
+}
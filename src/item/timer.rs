@@ -0,0 +1,117 @@
+use super::Item;
+use crate::attribute::Quality;
+use crate::describe::Describe;
+use crate::list::{List, ListTrait};
+use serde::{Deserialize, Serialize};
+
+/// `Timer` counts down the ticks until an item is gone, like a torch burning out or a corpse
+/// rotting away. A container leaves whatever was in it behind.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timer {
+    pub ticks: u32,
+    /// What's seen as the item goes
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl Timer {
+    pub fn new(ticks: u32, message: Option<String>) -> Self {
+        Self { ticks, message }
+    }
+
+    /// Counts down a tick, returning whether the time is up
+    pub fn tick(&mut self) -> bool {
+        self.ticks = self.ticks.saturating_sub(1);
+        self.is_up()
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+/// Ticks the timer of every item in the list, and of everything inside them, taking away any whose
+/// time is up. Returns what's seen as they go.
+pub fn tick_items(items: &mut List<Item, Quality>) -> Vec<String> {
+    let mut seen = vec![];
+    let mut any_up = false;
+    for item in items.iter_mut() {
+        if let Some(t) = item.timer_mut() {
+            any_up |= t.tick();
+        }
+        if let Item::Container(c) = item {
+            seen.extend(tick_items(c.contents_mut()));
+        }
+    }
+    if !any_up {
+        return seen;
+    }
+
+    while let Some(item) = items.take_where(|i| i.timer().map_or(false, Timer::is_up)) {
+        seen.push(match item.timer().and_then(|t| t.message.clone()) {
+            Some(msg) => msg,
+            None => format!("the {} is gone", item.name()),
+        });
+
+        if let Item::Container(c) = item {
+            for inner in c.into_items() {
+                items.insert_item(inner).ok();
+            }
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod timer_test {
+    use super::*;
+    use crate::game::game_test::items;
+
+    #[test]
+    fn test_tick_items() {
+        let x = r#"---
+inner:
+- Holdable:
+    name: torch
+    display: A torch burns here
+    description: It's a torch.
+    handle: [torch]
+    timer:
+      ticks: 2
+      message: the torch burns out
+- Container:
+    info:
+      name: block of ice
+      display: A block of ice is here
+      description: Something is frozen inside.
+      handle: [ice, block]
+      timer:
+        ticks: 3
+    inner:
+    - Holdable:
+        name: fish
+        display: A fish lies here
+        description: It's a fish.
+        handle: [fish]
+    - Holdable:
+        name: snowball
+        display: A snowball is here
+        description: It's already melting.
+        handle: [snowball]
+        timer:
+          ticks: 1
+          message: the snowball melts
+"#;
+        let mut items = items(x);
+
+        assert_eq!(tick_items(&mut items), vec!["the snowball melts"]);
+        assert_eq!(tick_items(&mut items), vec!["the torch burns out"]);
+        assert!(items.get_item("torch".into()).is_none());
+
+        assert_eq!(tick_items(&mut items), vec!["the block of ice is gone"]);
+        assert!(items.get_item("fish".into()).is_some());
+        assert!(items.get_item("snowball".into()).is_none());
+        assert!(tick_items(&mut items).is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
+
+/// `Dice` is a damage roll written the usual way, like `2d6+1`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+    pub bonus: i64,
+}
+
+impl Dice {
+    pub fn roll(&self) -> i64 {
+        let mut rng = thread_rng();
+        let total: i64 = (0..self.count)
+            .map(|_| rng.gen_range(1, self.sides as i64 + 1))
+            .sum();
+        total + self.bonus
+    }
+}
+
+impl Default for Dice {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            sides: 4,
+            bonus: 0,
+        }
+    }
+}
+
+impl TryFrom<String> for Dice {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let err = || format!("{} isn't a dice roll, like 2d6+1", s);
+
+        let (dice, bonus) = match s.find(|c| c == '+' || c == '-') {
+            Some(n) => {
+                let bonus = s[n..]
+                    .trim_start_matches('+')
+                    .parse::<i64>()
+                    .map_err(|_| err())?;
+                (&s[..n], bonus)
+            }
+            None => (&s[..], 0),
+        };
+
+        let mut parts = dice.splitn(2, 'd');
+        let count = match parts.next() {
+            Some("") => 1,
+            Some(n) => n.parse::<u32>().map_err(|_| err())?,
+            None => return Err(err()),
+        };
+        let sides = match parts.next() {
+            Some(n) => n.parse::<u32>().map_err(|_| err())?,
+            None => return Err(err()),
+        };
+
+        if sides == 0 {
+            return Err(err());
+        }
+
+        Ok(Self {
+            count,
+            sides,
+            bonus,
+        })
+    }
+}
+
+impl From<Dice> for String {
+    fn from(d: Dice) -> Self {
+        d.to_string()
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.bonus {
+            0 => write!(f, "{}d{}", self.count, self.sides),
+            b if b > 0 => write!(f, "{}d{}+{}", self.count, self.sides, b),
+            b => write!(f, "{}d{}{}", self.count, self.sides, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+    Slashing,
+    Piercing,
+    Bludgeoning,
+}
+
+impl DamageType {
+    /// How an attack of this type reads, to the attacker and to everyone else
+    pub fn verbs(&self) -> (&'static str, &'static str) {
+        match self {
+            DamageType::Slashing => ("slash", "slashes"),
+            DamageType::Piercing => ("stab", "stabs"),
+            DamageType::Bludgeoning => ("hit", "hits"),
+        }
+    }
+}
+
+impl Default for DamageType {
+    fn default() -> Self {
+        DamageType::Bludgeoning
+    }
+}
+
+/// `WeaponStats` says how much damage a weapon does, and how quickly. `speed` is the number of
+/// times it strikes each round.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeaponStats {
+    pub damage: Dice,
+    #[serde(default)]
+    pub kind: DamageType,
+    #[serde(default = "default_speed")]
+    pub speed: u32,
+}
+
+fn default_speed() -> u32 {
+    1
+}
+
+impl Default for WeaponStats {
+    fn default() -> Self {
+        Self {
+            damage: Dice::default(),
+            kind: DamageType::default(),
+            speed: default_speed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod weapon_test {
+    use super::*;
+
+    #[test]
+    fn test_dice() {
+        let d = Dice::try_from("2d6+1".to_owned()).unwrap();
+        assert_eq!((d.count, d.sides, d.bonus), (2, 6, 1));
+        assert_eq!(d.to_string(), "2d6+1");
+
+        let d = Dice::try_from("d8-2".to_owned()).unwrap();
+        assert_eq!((d.count, d.sides, d.bonus), (1, 8, -2));
+
+        assert!(Dice::try_from("sword".to_owned()).is_err());
+        assert!(Dice::try_from("2d0".to_owned()).is_err());
+
+        for _ in 0..100 {
+            let n = Dice::try_from("3d4".to_owned()).unwrap().roll();
+            assert!((3..=12).contains(&n));
+        }
+    }
+
+    #[test]
+    fn test_weapon_yaml() {
+        let x = r#"---
+damage: 1d8
+kind: Slashing
+"#;
+        let w: WeaponStats = serde_yaml::from_str(x).unwrap();
+        assert_eq!(w.damage.to_string(), "1d8");
+        assert_eq!(w.kind.verbs(), ("slash", "slashes"));
+        assert_eq!(w.speed, 1);
+    }
+}
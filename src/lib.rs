@@ -3,14 +3,14 @@
 #![feature(backtrace)]
 
 pub mod attribute;
-mod db;
+pub mod db;
 pub mod describe;
 pub mod error;
 pub mod fight;
 pub mod game;
 pub mod hook;
 mod interpreter;
-mod item;
+pub mod item;
 pub mod list;
 pub mod location;
 pub mod map;
@@ -34,6 +34,16 @@ impl<T: Default, U> List<T, U> {
     pub fn into_inner(mut self) -> Vec<T> {
         take(&mut self.inner)
     }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.inner.iter_mut()
+    }
+
+    /// Removes and returns the first item that matches
+    pub fn take_where<F: Fn(&T) -> bool>(&mut self, f: F) -> Option<T> {
+        let pos = self.inner.iter().position(f)?;
+        Some(self.inner.remove(pos))
+    }
 }
 
 impl<T: Default, U> IntoIterator for List<T, U> {
@@ -84,8 +94,37 @@ impl<T: Default + Send + Sync + Debug> Attribute<Quality> for List<T, Quality> {
     }
 }
 
+/// `Stack` is for things that pile up, like coins or arrows, so that any number of them take
+/// up a single place in a list. Anything else is a stack of one that never merges.
+pub trait Stack: Sized {
+    fn count(&self) -> u32 {
+        1
+    }
+
+    /// Piles the other stack on top of this one, handing it back if the two don't stack
+    fn merge(&mut self, other: Self) -> Result<(), Self> {
+        Err(other)
+    }
+
+    /// Takes `n` off the stack, so long as that leaves something behind
+    fn split(&mut self, _n: u32) -> Option<Self> {
+        None
+    }
+
+    /// The name, along with how many there are when there's more than one
+    fn label(&self) -> String
+    where
+        Self: Describe,
+    {
+        match self.count() {
+            1 => self.name(),
+            n => format!("{} (x{})", self.name(), n),
+        }
+    }
+}
+
 pub trait ListTrait: Describe + Debug {
-    type Item: Describe + Default + Debug;
+    type Item: Describe + Default + Debug + Stack;
 
     fn get_item(&self, handle: Grabber) -> Option<&Self::Item>;
     fn get_item_mut(&mut self, handle: Grabber) -> Option<&mut Self::Item>;
@@ -94,22 +133,50 @@ pub trait ListTrait: Describe + Debug {
     fn list(&self) -> Vec<&Self::Item>;
     fn display_items(&self) -> String;
 
+    /// Takes `n` of an item out of the list, splitting its stack if there are more than that
+    fn get_some_owned(&mut self, handle: Grabber, n: u32) -> Result<Self::Item, EnnuiError> {
+        let item = self
+            .get_item_mut(handle)
+            .ok_or(EnnuiError::Simple(CmdErr::ItemNotFound))?;
+
+        match item.count() {
+            count if count < n => Err(EnnuiError::Simple(CmdErr::NotEnough)),
+            count if count == n => self.get_item_owned(handle),
+            _ => item
+                .split(n)
+                .ok_or(EnnuiError::Simple(CmdErr::ItemNotFound)),
+        }
+    }
+
     fn transfer(
         &mut self,
         other: &mut ListTrait<Item = Self::Item>,
         handle: &str,
     ) -> Result<String, EnnuiError> {
-        let item = self.get_item_owned(handle.into())?;
+        self.transfer_some(other, handle, 1)
+    }
 
-        let name = item.name();
-        if other.insert_item(item).is_err() {
-            return Err(EnnuiError::Fatal("COULD NOT TRANSFER ITEM".into()));
+    /// Moves `n` of an item into the other list, where it joins any stack already there
+    fn transfer_some(
+        &mut self,
+        other: &mut dyn ListTrait<Item = Self::Item>,
+        handle: &str,
+        n: u32,
+    ) -> Result<String, EnnuiError> {
+        let item = self.get_some_owned(handle.into(), n)?;
+
+        let name = item.label();
+        if let Err(item) = other.insert_item(item) {
+            if self.insert_item(item).is_err() {
+                return Err(EnnuiError::Fatal("COULD NOT TRANSFER ITEM".into()));
+            }
+            return Err(EnnuiError::Simple(CmdErr::CannotAcceptGivenItem));
         };
         Ok(name)
     }
 }
 
-impl<T: Describe + Default + Debug, U: Send + Sync + Debug> ListTrait for List<T, U> {
+impl<T: Describe + Default + Debug + Stack, U: Send + Sync + Debug> ListTrait for List<T, U> {
     type Item = T;
 
     fn get_item(&self, handle: Grabber) -> Option<&T> {
@@ -158,7 +225,10 @@ impl<T: Describe + Default + Debug, U: Send + Sync + Debug> ListTrait for List<T
             &self
                 .list()
                 .iter()
-                .map(|i| i.display().color(Color::Green))
+                .map(|i| match i.count() {
+                    1 => i.display().color(Color::Green),
+                    n => format!("{} (x{})", i.display(), n).color(Color::Green),
+                })
                 .collect::<Vec<_>>()
                 .join("\n"),
         );
@@ -166,7 +236,13 @@ impl<T: Describe + Default + Debug, U: Send + Sync + Debug> ListTrait for List<T
         ret
     }
 
-    fn insert_item(&mut self, item: T) -> Result<(), T> {
+    fn insert_item(&mut self, mut item: T) -> Result<(), T> {
+        for other in self.inner.iter_mut() {
+            item = match other.merge(item) {
+                Ok(()) => return Ok(()),
+                Err(item) => item,
+            };
+        }
         self.inner.push(item);
         Ok(())
     }
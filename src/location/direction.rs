@@ -8,26 +8,28 @@ pub enum MapDir {
     South,
     East,
     West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
     Up,
     Down,
-    NorthEast,
     NoneFound,
-    // etc
 }
 
 impl From<&str> for MapDir {
     fn from(d: &str) -> Self {
-        let sw = |a: &str, b: &str| a.starts_with(b);
-        match d {
-            s if sw(MapDir::North.to_string(), s) => MapDir::North,
-            s if sw(MapDir::South.to_string(), s) => MapDir::South,
-            s if sw(MapDir::East.to_string(), s) => MapDir::East,
-            s if sw(MapDir::West.to_string(), s) => MapDir::West,
-            s if sw(MapDir::North.to_string(), s) => MapDir::North,
-            s if sw(MapDir::Down.to_string(), s) => MapDir::Down,
-            s if sw(MapDir::NorthEast.to_string(), s) => MapDir::NorthEast,
-            _ => NoneFound,
+        if d.is_empty() {
+            return NoneFound;
         }
+
+        // the short forms come first, so that `ne` isn't taken as the start of `north`
+        let all = MapDir::all();
+        all.iter()
+            .find(|dir| dir.to_string_short() == d)
+            .or_else(|| all.iter().find(|dir| MapDir::to_string(dir).starts_with(d)))
+            .copied()
+            .unwrap_or(NoneFound)
     }
 }
 
@@ -38,14 +40,17 @@ impl Default for MapDir {
 }
 
 impl MapDir {
-    const ALL_DIRS: [MapDir; 7] = [
+    const ALL_DIRS: [MapDir; 10] = [
         MapDir::North,
         MapDir::South,
         MapDir::East,
         MapDir::West,
+        MapDir::NorthEast,
+        MapDir::NorthWest,
+        MapDir::SouthEast,
+        MapDir::SouthWest,
         MapDir::Up,
         MapDir::Down,
-        MapDir::NorthEast,
     ];
 
     pub fn all() -> &'static [Self] {
@@ -58,9 +63,12 @@ impl MapDir {
             MapDir::South => "south",
             MapDir::East => "east",
             MapDir::West => "west",
+            MapDir::NorthEast => "northeast",
+            MapDir::NorthWest => "northwest",
+            MapDir::SouthEast => "southeast",
+            MapDir::SouthWest => "southwest",
             MapDir::Up => "up",
             MapDir::Down => "down",
-            MapDir::NorthEast => "northeast",
             MapDir::NoneFound => "",
         }
     }
@@ -70,9 +78,12 @@ impl MapDir {
             MapDir::South => "s",
             MapDir::East => "e",
             MapDir::West => "w",
+            MapDir::NorthEast => "ne",
+            MapDir::NorthWest => "nw",
+            MapDir::SouthEast => "se",
+            MapDir::SouthWest => "sw",
             MapDir::Up => "u",
             MapDir::Down => "d",
-            MapDir::NorthEast => "ne",
             MapDir::NoneFound => "",
         }
     }
@@ -83,9 +94,12 @@ impl MapDir {
             MapDir::South => MapDir::North,
             MapDir::East => MapDir::West,
             MapDir::West => MapDir::East,
+            MapDir::NorthEast => MapDir::SouthWest,
+            MapDir::NorthWest => MapDir::SouthEast,
+            MapDir::SouthEast => MapDir::NorthWest,
+            MapDir::SouthWest => MapDir::NorthEast,
             MapDir::Up => MapDir::Down,
             MapDir::Down => MapDir::Up,
-            MapDir::NorthEast => MapDir::NoneFound,
             MapDir::NoneFound => MapDir::NoneFound,
         }
     }
@@ -93,41 +107,36 @@ impl MapDir {
 
 impl std::fmt::Debug for MapDir {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        use MapDir::*;
-        write!(
-            f,
-            "{}",
-            match self {
-                North => "north",
-                South => "south",
-                East => "east",
-                West => "west",
-                Up => "up",
-                Down => "down",
-                NorthEast => "northeast",
-                NoneFound => "",
-            }
-        )
+        write!(f, "{}", self.to_string())
     }
 }
 
 impl Display for MapDir {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        use MapDir::*;
+        write!(f, "{}", self.to_string_short())
+    }
+}
+
+#[cfg(test)]
+mod direction_test {
+    use super::*;
 
-        write!(
-            f,
-            "{}",
-            match self {
-                North => "n",
-                South => "s",
-                East => "e",
-                West => "w",
-                Up => "u",
-                Down => "d",
-                NorthEast => "ne",
-                NoneFound => "",
-            }
-        )
+    #[test]
+    fn test_dir_from_str() {
+        assert_eq!(MapDir::from("n"), MapDir::North);
+        assert_eq!(MapDir::from("ne"), MapDir::NorthEast);
+        assert_eq!(MapDir::from("northw"), MapDir::NorthWest);
+        assert_eq!(MapDir::from("sw"), MapDir::SouthWest);
+        assert_eq!(MapDir::from("u"), MapDir::Up);
+        assert_eq!(MapDir::from("down"), MapDir::Down);
+        assert_eq!(MapDir::from("x"), MapDir::NoneFound);
+        assert_eq!(MapDir::from(""), MapDir::NoneFound);
+    }
+
+    #[test]
+    fn test_opposites() {
+        for dir in MapDir::all() {
+            assert_eq!(dir.opposite().opposite(), *dir);
+        }
     }
 }
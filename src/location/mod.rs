@@ -2,33 +2,63 @@ pub mod direction;
 use direction::MapDir;
 
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
 pub trait Locate {
     fn loc(&self) -> Coord;
 }
 
+/// `Coord` is a position in the world, as `(x, y, z)`. Maps written before the z-axis existed
+/// give only `[x, y]`, and are placed at ground level.
 #[derive(Eq, PartialEq, Debug, Deserialize, Serialize, Hash, Default, Clone, Copy)]
-pub struct Coord(pub i64, pub i64);
+#[serde(from = "CoordRepr")]
+pub struct Coord(pub i64, pub i64, pub i64);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CoordRepr {
+    Full(i64, i64, i64),
+    Flat(i64, i64),
+}
+
+impl From<CoordRepr> for Coord {
+    fn from(other: CoordRepr) -> Self {
+        match other {
+            CoordRepr::Full(x, y, z) => Coord(x, y, z),
+            CoordRepr::Flat(x, y) => Coord(x, y, 0),
+        }
+    }
+}
 
 impl Coord {
     pub fn north(&self) -> Self {
-        let Coord(x, y) = self;
-        Coord(*x, *y + 1)
+        let Coord(x, y, z) = self;
+        Coord(*x, *y + 1, *z)
     }
 
     pub fn south(&self) -> Self {
-        let Coord(x, y) = self;
-        Coord(*x, *y - 1)
+        let Coord(x, y, z) = self;
+        Coord(*x, *y - 1, *z)
     }
 
     pub fn east(&self) -> Self {
-        let Coord(x, y) = self;
-        Coord(*x + 1, *y)
+        let Coord(x, y, z) = self;
+        Coord(*x + 1, *y, *z)
     }
 
     pub fn west(&self) -> Self {
-        let Coord(x, y) = self;
-        Coord(*x - 1, *y)
+        let Coord(x, y, z) = self;
+        Coord(*x - 1, *y, *z)
+    }
+
+    pub fn up(&self) -> Self {
+        let Coord(x, y, z) = self;
+        Coord(*x, *y, *z + 1)
+    }
+
+    pub fn down(&self) -> Self {
+        let Coord(x, y, z) = self;
+        Coord(*x, *y, *z - 1)
     }
 
     pub fn add(&self, dir: MapDir) -> Option<Self> {
@@ -39,32 +69,86 @@ impl Coord {
             South => self.south(),
             East => self.east(),
             West => self.west(),
-            _ => return None,
+            NorthEast => self.north().east(),
+            NorthWest => self.north().west(),
+            SouthEast => self.south().east(),
+            SouthWest => self.south().west(),
+            Up => self.up(),
+            Down => self.down(),
+            NoneFound => return None,
         })
     }
 }
 
+/// Coordinates may be written either as `x y [z]` or as `x,y[,z]`
+impl TryFrom<&[&str]> for Coord {
+    type Error = String;
+
+    fn try_from(o: &[&str]) -> Result<Self, Self::Error> {
+        let joined = o.join(" ").replace(',', " ");
+        let nums = joined
+            .split_whitespace()
+            .map(|n| n.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("{}", e))?;
+
+        match nums.as_slice() {
+            [x, y] => Ok(Coord(*x, *y, 0)),
+            [x, y, z] => Ok(Coord(*x, *y, *z)),
+            _ => Err("coordinates must have two or three numbers".into()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod coord_test {
     use super::*;
 
     #[test]
     fn coord_test_north() {
-        assert_eq!(Coord(0, 0).north(), Coord(0, 1));
+        assert_eq!(Coord(0, 0, 0).north(), Coord(0, 1, 0));
     }
 
     #[test]
     fn coord_test_south() {
-        assert_eq!(Coord(0, 0).south(), Coord(0, -1));
+        assert_eq!(Coord(0, 0, 0).south(), Coord(0, -1, 0));
     }
 
     #[test]
     fn coord_test_east() {
-        assert_eq!(Coord(0, 0).east(), Coord(1, 0));
+        assert_eq!(Coord(0, 0, 0).east(), Coord(1, 0, 0));
     }
 
     #[test]
     fn coord_test_west() {
-        assert_eq!(Coord(0, 0).west(), Coord(-1, 0));
+        assert_eq!(Coord(0, 0, 0).west(), Coord(-1, 0, 0));
+    }
+
+    #[test]
+    fn coord_test_diagonals() {
+        assert_eq!(Coord(0, 0, 0).add(MapDir::NorthEast), Some(Coord(1, 1, 0)));
+        assert_eq!(
+            Coord(0, 0, 0).add(MapDir::SouthWest),
+            Some(Coord(-1, -1, 0))
+        );
+        assert_eq!(Coord(0, 0, 0).add(MapDir::Up), Some(Coord(0, 0, 1)));
+        assert_eq!(Coord(0, 0, 0).add(MapDir::NoneFound), None);
+    }
+
+    #[test]
+    fn coord_test_flat_maps_still_load() {
+        let c: Coord = serde_yaml::from_str("[1, 2]").unwrap();
+        assert_eq!(c, Coord(1, 2, 0));
+        let c: Coord = serde_yaml::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(c, Coord(1, 2, 3));
+    }
+
+    #[test]
+    fn coord_test_try_from() {
+        assert_eq!(Coord::try_from(&["2", "-5"][..]), Ok(Coord(2, -5, 0)));
+        assert_eq!(Coord::try_from(&["2,5"][..]), Ok(Coord(2, 5, 0)));
+        assert_eq!(Coord::try_from(&["2", "5", "-1"][..]), Ok(Coord(2, 5, -1)));
+        assert!(Coord::try_from(&["2"][..]).is_err());
+        assert!(Coord::try_from(&["north"][..]).is_err());
     }
 }
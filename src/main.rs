@@ -71,4 +71,61 @@
 ////     Ok(())
 //// }
 //
-fn main() {}
+use ennui::db::{self, migrate, Account};
+use ennui::game::item_templates;
+use ennui::player::Role;
+use std::env::args;
+use std::error::Error;
+use std::io::BufRead;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["migrate", "up"] => {
+            let mut db = db::connect_unchecked()?;
+            for v in migrate::up(&mut *db)? {
+                println!("ran migration {:04}", v);
+            }
+            db.seed()?;
+            if db.templates()?.is_empty() {
+                for t in item_templates()? {
+                    db.save_template(&t)?;
+                }
+            }
+            print!("{}", migrate::status(&mut *db)?);
+        }
+        ["migrate", "status"] => {
+            let mut db = db::connect_unchecked()?;
+            print!("{}", migrate::status(&mut *db)?);
+        }
+        ["account", name, role] => {
+            let role: Role = serde_yaml::from_str(role)?;
+            eprint!("password for {}: ", name);
+            let mut password = String::new();
+            std::io::stdin().lock().read_line(&mut password)?;
+            let password = password.trim_end_matches(&['\r', '\n'][..]);
+            if password.is_empty() {
+                return Err("the password can't be empty".into());
+            }
+
+            let mut db = db::connect()?;
+            db.save_account(name, &Account::new(role, password))?;
+            println!("{} is now {:?}", name, role);
+        }
+        _ => {
+            eprintln!("usage: ennui migrate [up/status]");
+            eprintln!("       ennui account <name> [Player/Builder/Admin]");
+        }
+    }
+
+    Ok(())
+}
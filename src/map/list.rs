@@ -17,6 +17,10 @@ impl Uuid for RoomList {
 pub trait RoomListTrait {
     fn player_ids(&self, loc: Coord) -> PlayerIdList;
     fn exits(&self, loc: Coord) -> Vec<MapDir>;
+    fn dest(&self, loc: Coord, dir: MapDir) -> Option<Coord>;
+    /// Whether the exit leads somewhere other than the neighbouring room, in which case any door
+    /// on it has no twin on the far side
+    fn off_grid(&self, loc: Coord, dir: MapDir) -> bool;
 }
 
 impl RoomListTrait for RoomList {
@@ -31,7 +35,7 @@ impl RoomListTrait for RoomList {
         MapDir::all()
             .iter()
             .filter_map(|d| {
-                if self.contains_key(&loc.add(*d)?) {
+                if self.contains_key(&self.dest(loc, *d)?) {
                     Some(*d)
                 } else {
                     None
@@ -39,4 +43,116 @@ impl RoomListTrait for RoomList {
             })
             .collect()
     }
+
+    /// The room reached by leaving `loc` in the given direction. An exit declared by the room
+    /// leads wherever it says, or nowhere, and a door with an `alt_dest` likewise; otherwise the
+    /// exit leads to the neighbouring room.
+    fn dest(&self, loc: Coord, dir: MapDir) -> Option<Coord> {
+        let room = self.get(&loc);
+        if let Some(exit) = room.and_then(|r| r.exit(dir)) {
+            return exit;
+        }
+
+        let alt_dest = room
+            .and_then(|r| r.doors().get(&dir))
+            .and_then(|d| d.alt_dest());
+
+        alt_dest.or_else(|| loc.add(dir))
+    }
+
+    fn off_grid(&self, loc: Coord, dir: MapDir) -> bool {
+        self.dest(loc, dir) != loc.add(dir)
+    }
+}
+
+#[cfg(test)]
+mod room_list_test {
+    use super::*;
+    use crate::location::Locate;
+
+    #[test]
+    fn test_alt_dest() {
+        let x = r#"---
+- loc: [0, 0]
+  name: A Cell
+  description: ""
+  display: ""
+  handle: []
+  doors:
+    Up:
+      dir: Up
+      alt_dest: [5, 5, 2]
+    North:
+      dir: North
+      alt_dest: [1, 1]
+- loc: [0, 1]
+  name: A Corridor
+  description: ""
+  display: ""
+  handle: []
+- loc: [1, 1]
+  name: A Crooked Corridor
+  description: ""
+  display: ""
+  handle: []
+- loc: [5, 5, 2]
+  name: A Tower
+  description: ""
+  display: ""
+  handle: []
+"#;
+        let v: Vec<Room> = serde_yaml::from_str(x).unwrap();
+        let rooms: RoomList = v.into_iter().map(|r| (r.loc(), r)).collect();
+
+        let origin = Coord(0, 0, 0);
+        assert_eq!(rooms.dest(origin, MapDir::Up), Some(Coord(5, 5, 2)));
+        assert_eq!(rooms.dest(origin, MapDir::North), Some(Coord(1, 1, 0)));
+        assert_eq!(rooms.dest(Coord(0, 1, 0), MapDir::South), Some(origin));
+        assert_eq!(
+            rooms.exits(origin),
+            vec![MapDir::North, MapDir::NorthEast, MapDir::Up]
+        );
+        assert!(rooms.exits(Coord(5, 5, 2)).is_empty());
+    }
+
+    #[test]
+    fn test_exits() {
+        let x = r#"---
+- loc: [0, 0]
+  name: A Cell
+  description: ""
+  display: ""
+  handle: []
+  exits:
+    West: [40, -3, 1]
+- loc: [0, 1]
+  name: A Slide
+  description: ""
+  display: ""
+  handle: []
+  exits:
+    South: ~
+    North: [0, 0]
+- loc: [40, -3, 1]
+  name: A Distant Hut
+  description: ""
+  display: ""
+  handle: []
+"#;
+        let v: Vec<Room> = serde_yaml::from_str(x).unwrap();
+        let rooms: RoomList = v.into_iter().map(|r| (r.loc(), r)).collect();
+
+        let origin = Coord(0, 0, 0);
+        let slide = Coord(0, 1, 0);
+        assert_eq!(rooms.dest(origin, MapDir::North), Some(slide));
+        assert_eq!(rooms.dest(origin, MapDir::West), Some(Coord(40, -3, 1)));
+        assert_eq!(rooms.exits(origin), vec![MapDir::North, MapDir::West]);
+        assert!(!rooms.off_grid(origin, MapDir::North));
+        assert!(rooms.off_grid(origin, MapDir::West));
+
+        // the slide only goes one way
+        assert_eq!(rooms.dest(slide, MapDir::South), None);
+        assert_eq!(rooms.dest(slide, MapDir::North), Some(origin));
+        assert_eq!(rooms.exits(slide), vec![MapDir::North]);
+    }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::location::direction::MapDir;
 
@@ -6,10 +7,12 @@ use crate::attribute::{Attribute, Quality};
 use crate::describe::Describe;
 use crate::error::EnnuiError;
 use crate::hook::{Grabber, Hook};
+use crate::item::template::Templates;
+use crate::item::timer::tick_items;
 use crate::item::{DescriptionWithQualities, Item, YamlItemList};
 use crate::list::{List, ListTrait};
 use crate::location::{Coord, Locate};
-use crate::obstacle::door::DoorList;
+use crate::obstacle::door::{DoorList, DoorState, ObstacleState};
 use crate::player::list::PlayerIdList;
 use crate::text::message::MessageFormat;
 use crate::text::Color::{Cyan, Green};
@@ -29,6 +32,56 @@ pub struct Room {
     inner_items: Option<YamlItemList>,
     #[serde(default)]
     doors: DoorList,
+    /// Exits that don't follow the grid, by direction: either the room the exit leads to, which
+    /// may be anywhere on the map, or `~` for no exit at all. Any other direction leads to the
+    /// neighbouring room, if there is one.
+    #[serde(default)]
+    exits: HashMap<MapDir, Option<Coord>>,
+    #[serde(default)]
+    reset: Option<Reset>,
+    /// The room's items as the map describes them, kept so they can be put back on reset
+    #[serde(skip_serializing, skip_deserializing)]
+    original_items: YamlItemList,
+    /// Likewise the states of its doors
+    #[serde(skip_serializing, skip_deserializing)]
+    original_doors: HashMap<MapDir, DoorState>,
+}
+
+/// `Reset` puts a room back the way the map describes it, every `every` ticks: any of its items
+/// that have been taken are replaced, its doors are opened or shut again, and any of the NPCs
+/// named in `npcs` who have been killed come back. Rooms with players in them aren't reset,
+/// unless `when` is `Always`.
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct Reset {
+    #[serde(default = "default_reset")]
+    pub every: u32,
+    #[serde(default)]
+    pub when: ResetWhen,
+    /// The handles of the NPCs, from `npcs.yaml`, who live here
+    #[serde(default)]
+    pub npcs: Vec<String>,
+    #[serde(skip)]
+    since_reset: u32,
+    /// The NPC living here under each of those handles, so that others sharing their names
+    /// elsewhere don't stand in for them
+    #[serde(skip)]
+    residents: HashMap<String, u128>,
+}
+
+fn default_reset() -> u32 {
+    20
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum ResetWhen {
+    Empty,
+    Always,
+}
+
+impl Default for ResetWhen {
+    fn default() -> Self {
+        ResetWhen::Empty
+    }
 }
 
 pub trait Space: Locate + ListTrait {
@@ -126,15 +179,101 @@ impl Space for Room {
 }
 
 impl Room {
-    pub fn init(&mut self) {
-        let inner = self.inner_items.take().unwrap_or_default();
+    /// Builds the room's items, swapping any references for copies of their templates
+    pub fn init(&mut self, templates: &Templates) -> Result<(), EnnuiError> {
+        let mut inner = self.inner_items.take().unwrap_or_default();
+        templates.resolve(&mut inner)?;
+        if self.reset.is_some() {
+            self.original_items = inner.clone();
+            self.original_doors = self
+                .doors
+                .iter()
+                .map(|(dir, door)| (*dir, door.state()))
+                .collect();
+        }
         self.items = inner.into();
+        Ok(())
+    }
+
+    /// Ticks the timers of the items lying here, returning what's seen as any of them go
+    pub fn tick_items(&mut self) -> Vec<String> {
+        tick_items(&mut self.items)
+    }
+
+    /// Makes an NPC arriving in the world here at home, if the room is missing one by its handle
+    pub fn claim_resident(&mut self, handle: &Hook, u: u128) {
+        let reset = match self.reset.as_mut() {
+            Some(reset) => reset,
+            None => return,
+        };
+        let residents = &reset.residents;
+        if let Some(npc) = reset
+            .npcs
+            .iter()
+            .find(|npc| npc.as_str() == handle && !residents.contains_key(*npc))
+        {
+            reset.residents.insert(npc.clone(), u);
+        }
+    }
+
+    /// Counts down to the room's next reset. Returns the NPCs who live here but aren't `alive` if
+    /// it's time, and `None` if not, or if the room never resets.
+    pub fn tick_reset(
+        &mut self,
+        occupied: bool,
+        alive: impl Fn(u128) -> bool,
+    ) -> Option<Vec<String>> {
+        let reset = self.reset.as_mut()?;
+        reset.since_reset = reset.since_reset.saturating_add(1);
+        if reset.since_reset < reset.every || (occupied && reset.when == ResetWhen::Empty) {
+            return None;
+        }
+        reset.since_reset = 0;
+
+        self.original_items.refill(&mut self.items);
+        for (dir, state) in self.original_doors.iter() {
+            if let Some(door) = self.doors.get_mut(dir) {
+                door.set_state(state.clone());
+            }
+        }
+        reset.residents.retain(|_, u| alive(*u));
+        let residents = &reset.residents;
+        Some(
+            reset
+                .npcs
+                .iter()
+                .filter(|npc| !residents.contains_key(*npc))
+                .cloned()
+                .collect(),
+        )
     }
 
     pub fn doors(&self) -> &DoorList {
         &self.doors
     }
 
+    pub fn items(&self) -> &List<Item, Quality> {
+        &self.items
+    }
+
+    pub fn set_items(&mut self, items: List<Item, Quality>) {
+        self.items = items;
+    }
+
+    pub fn doors_mut(&mut self) -> &mut DoorList {
+        &mut self.doors
+    }
+
+    /// Where the room declares the exit in the given direction to lead, if it declares one at all.
+    /// `Some(None)` means there's no way out in that direction.
+    pub fn exit(&self, dir: MapDir) -> Option<Option<Coord>> {
+        self.exits.get(&dir).copied()
+    }
+
+    pub fn take_items(&mut self) -> List<Item, Quality> {
+        std::mem::take(&mut self.items)
+    }
+
     pub fn display(&self) -> String {
         eprintln!("[{}]: room.display", "SUCCESS".color(Green));
         eprintln!("in file {} on line number {}", file!(), line!());
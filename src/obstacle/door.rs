@@ -17,6 +17,15 @@ pub trait Lock<T>: ObstacleState<T> {
 
     fn unlock(&mut self, new_state: T, key: Option<&dyn Key<Self::Lock>>) -> StateResult<T>;
     fn is_locked(&self) -> bool;
+
+    /// Moves the obstacle toward locked. Obstacles can't be locked again unless they say so.
+    fn lock(&mut self, _new_state: T, _key: Option<&dyn Key<Self::Lock>>) -> StateResult<T> {
+        Err(self.state())
+    }
+
+    fn keyhole(&self) -> Option<Self::Lock> {
+        None
+    }
 }
 
 impl Lock<DoorState> for Door {
@@ -27,34 +36,111 @@ impl Lock<DoorState> for Door {
         new_state: DoorState,
         key: Option<&dyn Key<Self::Lock>>,
     ) -> StateResult<DoorState> {
-        if self.state == new_state {
-            return Err(self.state.clone());
+        unlock_state(&mut self.state, self.keyhole, new_state, key)
+    }
+
+    fn is_locked(&self) -> bool {
+        !matches!(self.state(), DoorState::Open | DoorState::Closed)
+    }
+
+    fn lock(
+        &mut self,
+        new_state: DoorState,
+        key: Option<&dyn Key<Self::Lock>>,
+    ) -> StateResult<DoorState> {
+        lock_state(&mut self.state, self.keyhole, new_state, key)
+    }
+
+    fn keyhole(&self) -> Option<u64> {
+        self.keyhole
+    }
+}
+
+impl Lock<DoorState> for Lid {
+    type Lock = u64;
+
+    fn unlock(
+        &mut self,
+        new_state: DoorState,
+        key: Option<&dyn Key<Self::Lock>>,
+    ) -> StateResult<DoorState> {
+        unlock_state(&mut self.state, self.keyhole, new_state, key)
+    }
+
+    fn is_locked(&self) -> bool {
+        !matches!(self.state(), DoorState::Open | DoorState::Closed)
+    }
+
+    fn lock(
+        &mut self,
+        new_state: DoorState,
+        key: Option<&dyn Key<Self::Lock>>,
+    ) -> StateResult<DoorState> {
+        lock_state(&mut self.state, self.keyhole, new_state, key)
+    }
+
+    fn keyhole(&self) -> Option<u64> {
+        self.keyhole
+    }
+}
+
+/// Moves a door or lid toward open. Opening a closed door needs no key; anything further
+/// needs the key that fits the keyhole, if there is one.
+fn unlock_state(
+    state: &mut DoorState,
+    keyhole: Option<u64>,
+    new_state: DoorState,
+    key: Option<&dyn Key<u64>>,
+) -> StateResult<DoorState> {
+    if *state == new_state {
+        return Err(state.clone());
+    }
+
+    match (new_state.clone(), state.clone()) {
+        (Open, DoorState::Closed) => {
+            *state = new_state;
+            return Ok(());
         }
+        (DoorState::Closed, Open) => return Err(state.clone()),
+        _ => (),
+    }
 
-        match (new_state.clone(), self.state()) {
-            (Open, DoorState::Closed) => {
-                self.state = new_state;
-                return Ok(());
-            }
-            (DoorState::Closed, Open) => return Err(self.state()),
-            _ => (),
+    match (keyhole, key) {
+        (Some(h), Some(k)) if h == k.key() => {
+            *state = new_state;
+            Ok(())
         }
+        (None, _) => {
+            *state = new_state;
+            Ok(())
+        }
+        _ => Err(Locked),
+    }
+}
 
-        match (self.keyhole, key) {
-            (Some(h), Some(k)) if h == k.key() => {
-                self.state = new_state;
-                Ok(())
-            }
-            (None, _) => {
-                self.state = new_state;
-                Ok(())
-            }
-            _ => Err(Locked),
+/// Moves a door or lid toward locked. Closing needs no key, but locking needs the key that fits
+/// the keyhole.
+fn lock_state(
+    state: &mut DoorState,
+    keyhole: Option<u64>,
+    new_state: DoorState,
+    key: Option<&dyn Key<u64>>,
+) -> StateResult<DoorState> {
+    match (new_state.clone(), state.clone()) {
+        (DoorState::Closed, Open) => {
+            *state = new_state;
+            return Ok(());
         }
+        (Locked, DoorState::Closed) => (),
+        _ => return Err(state.clone()),
     }
 
-    fn is_locked(&self) -> bool {
-        !matches!(self.state(), DoorState::Open | DoorState::Closed)
+    match (keyhole, key) {
+        (Some(h), Some(k)) if h == k.key() => {
+            *state = new_state;
+            Ok(())
+        }
+        _ => Err(state.clone()),
     }
 }
 
@@ -114,7 +200,10 @@ impl Error for DoorState {}
 #[derive(Eq, PartialEq, Serialize, Deserialize, Default, Debug, Clone)]
 pub struct Door {
     dir: MapDir,
+    #[serde(default)]
     state: DoorState,
+    // where the door leads, if not to the neighbouring room in its direction
+    #[serde(default)]
     alt_dest: Option<Coord>,
     // the key's id must match the Keyhole's id
     keyhole: Option<u64>,
@@ -126,4 +215,69 @@ impl ObstacleState<DoorState> for Door {
     }
 }
 
+impl Door {
+    pub fn alt_dest(&self) -> Option<Coord> {
+        self.alt_dest
+    }
+
+    /// Sets the state outright, without a key. Used to keep the two sides of a door in step.
+    pub fn set_state(&mut self, state: DoorState) {
+        self.state = state;
+    }
+}
+
 pub type DoorList = HashMap<MapDir, Door>;
+
+/// `Lid` lets a container be closed and locked, like a door
+#[derive(Eq, PartialEq, Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Lid {
+    #[serde(default)]
+    state: DoorState,
+    #[serde(default)]
+    keyhole: Option<u64>,
+}
+
+impl ObstacleState<DoorState> for Lid {
+    fn state(&self) -> DoorState {
+        self.state.clone()
+    }
+}
+
+impl Lid {
+    pub fn is_open(&self) -> bool {
+        self.state == Open
+    }
+}
+
+#[cfg(test)]
+mod door_test {
+    use super::*;
+    use crate::obstacle::key::KeyType;
+
+    fn key(n: u64) -> KeyType {
+        KeyType {
+            info: Default::default(),
+            key: n,
+        }
+    }
+
+    #[test]
+    fn test_close_and_lock() {
+        let mut door = Door {
+            dir: MapDir::North,
+            state: Open,
+            alt_dest: None,
+            keyhole: Some(1),
+        };
+
+        assert_eq!(door.lock(Locked, Some(&key(1))), Err(Open));
+        assert_eq!(door.lock(DoorState::Closed, None), Ok(()));
+        assert_eq!(door.lock(DoorState::Closed, None), Err(DoorState::Closed));
+        assert_eq!(door.lock(Locked, Some(&key(2))), Err(DoorState::Closed));
+        assert_eq!(door.lock(Locked, Some(&key(1))), Ok(()));
+        assert!(door.is_locked());
+
+        assert_eq!(door.unlock(DoorState::Closed, Some(&key(1))), Ok(()));
+        assert_eq!(door.unlock(Open, None), Ok(()));
+    }
+}
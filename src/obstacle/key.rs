@@ -1,16 +1,17 @@
-use crate::attribute::{Attribute, Quality};
-use crate::describe::{Describe, Description};
+use crate::attribute::Quality;
+use crate::describe::Describe;
 use crate::hook::Hook;
+use crate::item::timer::Timer;
+use crate::item::{DescriptionWithQualities, ItemDescribe};
 use std::fmt::Debug;
 
-pub trait Key<T>: Describe + Debug + Attribute<Quality> {
+pub trait Key<T>: ItemDescribe + Debug {
     fn key(&self) -> T;
 }
 
 #[derive(Clone, Debug)]
 pub struct KeyType {
-    pub info: Description,
-    pub attr: Vec<Quality>,
+    pub info: DescriptionWithQualities,
     pub key: u64,
 }
 
@@ -20,7 +21,7 @@ impl KeyType {
     }
 
     pub fn add_quality(&mut self, q: Quality) {
-        self.attr.push(q);
+        self.info.attr.push(q);
     }
 }
 
@@ -42,6 +43,28 @@ impl Describe for KeyType {
     }
 }
 
+impl ItemDescribe for KeyType {
+    fn weight(&self) -> u32 {
+        self.info.weight()
+    }
+
+    fn price(&self) -> u64 {
+        self.info.price()
+    }
+
+    fn timer(&self) -> Option<&Timer> {
+        self.info.timer()
+    }
+
+    fn timer_mut(&mut self) -> Option<&mut Timer> {
+        self.info.timer_mut()
+    }
+
+    fn to_yaml(&self) -> DescriptionWithQualities {
+        self.info.clone()
+    }
+}
+
 impl Key<u64> for KeyType {
     fn key(&self) -> u64 {
         self.key
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// `Stat` names one of a player's meters, so that food and drink can say which one they touch
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Stat {
+    Hit,
+    Mana,
+    Movement,
+    Strength,
+    Dexterity,
+    Hunger,
+    Thirst,
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// `Effect` is what happens to a player who eats or drinks something
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Effect {
+    /// Tops a meter up, no higher than its maximum
+    Restore { stat: Stat, amount: i64 },
+    /// Raises (or lowers) a meter for a number of ticks
+    Buff { stat: Stat, amount: i64, ticks: u32 },
+    /// Hurts the player every tick, for a number of ticks
+    Poison { amount: i64, ticks: u32 },
+}
+
+impl Effect {
+    /// What the player feels straight away
+    pub fn felt(&self) -> String {
+        match self {
+            Effect::Restore { stat, .. } => format!("you feel your {} return", stat),
+            Effect::Buff { stat, amount, .. } if *amount < 0 => {
+                format!("you feel your {} drain away", stat)
+            }
+            Effect::Buff { stat, .. } => format!("you feel your {} grow", stat),
+            Effect::Poison { .. } => "you don't feel so good".to_owned(),
+        }
+    }
+}
+
+/// `Food` describes something that can be eaten, or drunk if `drink` is set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Food {
+    #[serde(default)]
+    pub drink: bool,
+    /// How much hunger (or thirst, for a drink) it takes away
+    #[serde(default)]
+    pub fills: i64,
+    #[serde(default)]
+    pub effects: Vec<Effect>,
+}
+
+/// `ActiveEffect` is a buff or poison that's still wearing off
+#[derive(Debug, Clone)]
+pub struct ActiveEffect {
+    pub effect: Effect,
+    pub remaining: u32,
+}
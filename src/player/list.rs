@@ -3,8 +3,10 @@ use std::collections::{HashMap, HashSet};
 use crate::player::{PlayerType, Uuid};
 use crate::text::message::{MessageFormat, Messenger};
 
+use crate::attribute::Attribute;
 use crate::describe::Describe;
 use crate::location::{Coord, Locate};
+use crate::player::PlayerStatus::WizInvis;
 use crate::text::Color;
 use crate::text::Color::Yellow;
 use std::sync::{Arc, Mutex};
@@ -53,6 +55,7 @@ impl PlayerIdListTrait for PlayerIdList {
         players
             .from_ids(self)
             .iter()
+            .filter(|p| !p.is(WizInvis))
             .map(|p| p.display().color(Yellow).custom_padded("\n", ""))
             .collect::<Vec<_>>()
             .join("")
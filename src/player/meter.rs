@@ -2,6 +2,8 @@ use std::fmt::{Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+use super::effect::Stat;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum MeterKind {
     Hit(Meter),
@@ -11,6 +13,8 @@ pub enum MeterKind {
     Dexterity(Meter),
     Weight(Meter),
     Height(Meter),
+    Hunger(Meter),
+    Thirst(Meter),
 }
 
 impl Display for MeterKind {
@@ -63,9 +67,8 @@ impl MeterKind {
     fn safe_unwrap(&self) -> &Meter {
         use MeterKind::*;
         match self {
-            Hit(m) | Mana(m) | Movement(m) | Strength(m) | Dexterity(m) | Weight(m) | Height(m) => {
-                m
-            }
+            Hit(m) | Mana(m) | Movement(m) | Strength(m) | Dexterity(m) | Weight(m) | Height(m)
+            | Hunger(m) | Thirst(m) => m,
         }
     }
 
@@ -78,15 +81,31 @@ impl MeterKind {
             MeterKind::Dexterity(_) => "dexterity",
             MeterKind::Weight(_) => "weight",
             MeterKind::Height(_) => "height",
+            MeterKind::Hunger(_) => "hunger",
+            MeterKind::Thirst(_) => "thirst",
         }
     }
 
+    /// Whether this is the meter named by `stat`
+    pub fn is(&self, stat: Stat) -> bool {
+        use MeterKind::*;
+        matches!(
+            (self, stat),
+            (Hit(_), Stat::Hit)
+                | (Mana(_), Stat::Mana)
+                | (Movement(_), Stat::Movement)
+                | (Strength(_), Stat::Strength)
+                | (Dexterity(_), Stat::Dexterity)
+                | (Hunger(_), Stat::Hunger)
+                | (Thirst(_), Stat::Thirst)
+        )
+    }
+
     fn safe_unwrap_mut(&mut self) -> (&mut i64, &mut i64) {
         use MeterKind::*;
         match self {
-            Hit(m) | Mana(m) | Movement(m) | Strength(m) | Dexterity(m) | Weight(m) | Height(m) => {
-                (&mut m.0, &mut m.1)
-            }
+            Hit(m) | Mana(m) | Movement(m) | Strength(m) | Dexterity(m) | Weight(m) | Height(m)
+            | Hunger(m) | Thirst(m) => (&mut m.0, &mut m.1),
         }
     }
 }
@@ -9,7 +9,9 @@ use crate::attribute::{Attribute, Quality};
 use crate::describe::{Describe, Description};
 use crate::error::EnnuiError;
 use crate::hook::{Grabber, Hook};
-use crate::item::Item;
+use crate::item::timer::tick_items;
+use crate::item::weapon::WeaponStats;
+use crate::item::{Item, Slot};
 use crate::list::{List, ListTrait};
 use crate::location::{Coord, Locate};
 use crate::soul::SoulKind;
@@ -20,15 +22,33 @@ use crate::fight::FightMod::Leave;
 #[cfg(not(target_arch = "wasm32"))]
 use rand::{thread_rng, Rng};
 use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::net::Shutdown::Both;
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+pub mod effect;
 pub mod list;
 mod meter;
 pub mod npc;
 mod player_test;
+pub mod shop;
+use effect::{ActiveEffect, Effect, Food, Stat};
 use npc::YamlPlayer;
+use shop::Shop;
+
+const DEFAULT_STRENGTH: i64 = 10;
+const DEFAULT_DEXTERITY: i64 = 10;
+/// How much weight a player can carry for each point of strength
+const CARRY_PER_STRENGTH: u32 = 10;
+const ENCUMBERED_MOVE_COST: i64 = 3;
+/// How full a player is after a good meal, and how empty before they start to notice
+const FULL: i64 = 100;
+const PECKISH: i64 = 20;
+const STARTING_GOLD: u64 = 50;
+/// How many ticks a corpse lasts before it rots away, unless the world says otherwise
+pub const CORPSE_DECAY: u32 = 20;
 
 #[derive(Debug)]
 pub enum PlayerType {
@@ -45,20 +65,15 @@ impl Default for PlayerType {
 
 impl From<YamlPlayer> for PlayerType {
     fn from(other: YamlPlayer) -> Self {
-        let mut p = Player::new();
-        let YamlPlayer {
-            info,
-            ai_type,
-            loc,
-            soul,
-        } = other;
-        p.info = info;
-        p.loc = loc;
-        if let Some(t) = ai_type {
-            Self::Npc(npc::Npc::new(p, t, soul))
-        } else {
-            Self::Human(p)
+        if other.ai_type.is_some() || other.shop.is_some() {
+            return Self::Npc(other.into());
         }
+
+        let mut p = Player::new();
+        p.info = other.info;
+        p.loc = other.loc;
+        p.items = crate::item::YamlItemList::from(other.items).into();
+        Self::Human(p)
     }
 }
 
@@ -97,6 +112,22 @@ pub struct Player {
     pub stream: Option<TcpStream>,
     stats: Vec<MeterKind>,
     status: Vec<PlayerStatus>,
+    #[serde(default)]
+    role: Role,
+    #[serde(default)]
+    ignoring: Vec<String>,
+    #[serde(default)]
+    channels: Vec<String>,
+    #[serde(default)]
+    muted: Vec<String>,
+    #[serde(default)]
+    gold: u64,
+    #[serde(skip_serializing, skip_deserializing)]
+    reply_to: Option<u128>,
+    #[serde(skip_serializing, skip_deserializing)]
+    silenced_until: Option<Instant>,
+    #[serde(skip_serializing, skip_deserializing)]
+    effects: Vec<ActiveEffect>,
     #[serde(skip_serializing, skip_deserializing)]
     fight_sender: Option<Arc<Mutex<Sender<FightMod>>>>,
 }
@@ -107,6 +138,40 @@ pub enum PlayerStatus {
     Dead,
     Asleep,
     Sitting,
+    WizInvis,
+}
+
+/// `Role` is the privilege level of an account. Each role may use every command that the roles
+/// below it may use.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize, Debug)]
+pub enum Role {
+    Player,
+    Builder,
+    Admin,
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Player
+    }
+}
+
+impl Display for PlayerStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use PlayerStatus::*;
+
+        write!(
+            f,
+            "{}",
+            match self {
+                Fighting => "fighting",
+                Dead => "dead",
+                Asleep => "asleep",
+                Sitting => "sitting down",
+                WizInvis => "invisible",
+            }
+        )
+    }
 }
 
 impl Attribute<PlayerStatus> for PlayerType {
@@ -280,6 +345,8 @@ impl Player {
             Hit(Meter(100, 100)),
             Movement(Meter(100, 100)),
             Mana(Meter(100, 100)),
+            Strength(Meter(DEFAULT_STRENGTH, DEFAULT_STRENGTH)),
+            Dexterity(Meter(DEFAULT_DEXTERITY, DEFAULT_DEXTERITY)),
         ];
 
         let uuid = new_player_id();
@@ -296,13 +363,21 @@ impl Player {
                 handle: Hook(vec![]),
                 display: String::new(),
             },
-            loc: Coord(0, 0),
+            loc: Coord(0, 0, 0),
             items: List::new(),
             souls,
             clothing: List::new(),
             stream: None,
             fight_sender: None,
             status: vec![],
+            role: Role::Player,
+            ignoring: vec![],
+            channels: vec![],
+            muted: vec![],
+            gold: STARTING_GOLD,
+            reply_to: None,
+            silenced_until: None,
+            effects: vec![],
             stats,
         }
     }
@@ -337,6 +412,137 @@ impl PlayerType {
         .set(current - amt as i64);
     }
 
+    /// Sets hit points outright, no higher than their maximum
+    pub fn set_hp(&mut self, hp: i64) {
+        if let Some(m) = self.meter_mut(Stat::Hit) {
+            m.set(hp.max(0).min(m.max()));
+        }
+    }
+
+    fn meter_mut(&mut self, stat: Stat) -> Option<&mut MeterKind> {
+        self.safe_unwrap_mut().stats.iter_mut().find(|m| m.is(stat))
+    }
+
+    /// Adds `amount` to a meter, keeping it between zero and its maximum
+    fn adjust(&mut self, stat: Stat, amount: i64) {
+        if let Some(m) = self.meter_mut(stat) {
+            let val = (m.current() + amount).max(0).min(m.max());
+            m.set(val);
+        }
+    }
+
+    /// Gives the player hunger and thirst meters, for worlds where players need to eat
+    pub fn enable_hunger(&mut self) {
+        use meter::Meter;
+        use meter::MeterKind::*;
+        let stats = &mut self.safe_unwrap_mut().stats;
+        if !stats.iter().any(|m| m.is(Stat::Hunger)) {
+            stats.push(Hunger(Meter(FULL, FULL)));
+            stats.push(Thirst(Meter(FULL, FULL)));
+        }
+    }
+
+    /// Eats or drinks something, returning what the player notices
+    pub fn consume(&mut self, food: &Food) -> Vec<String> {
+        let stat = if food.drink {
+            Stat::Thirst
+        } else {
+            Stat::Hunger
+        };
+        self.adjust(stat, food.fills);
+
+        let mut felt = vec![];
+        for effect in &food.effects {
+            match effect {
+                Effect::Restore { stat, amount } => self.adjust(*stat, *amount),
+                Effect::Buff {
+                    stat,
+                    amount,
+                    ticks,
+                } => {
+                    // buffs may take a meter past its maximum, until they wear off
+                    if let Some(m) = self.meter_mut(*stat) {
+                        let val = m.current() + amount;
+                        m.set(val);
+                    }
+                    self.safe_unwrap_mut().effects.push(ActiveEffect {
+                        effect: effect.clone(),
+                        remaining: *ticks,
+                    });
+                }
+                Effect::Poison { ticks, .. } => {
+                    self.safe_unwrap_mut().effects.push(ActiveEffect {
+                        effect: effect.clone(),
+                        remaining: *ticks,
+                    });
+                }
+            }
+            felt.push(effect.felt());
+        }
+
+        felt
+    }
+
+    /// Moves time along for the player: poison does its damage, buffs wear off, and the player
+    /// gets a little hungrier and thirstier. Shopkeepers restock, and the things the player carries
+    /// or wears wear away. Returns what the player notices.
+    pub fn tick(&mut self) -> Vec<String> {
+        let mut felt = vec![];
+
+        if let PlayerType::Npc(npc) = self {
+            npc.restock();
+        }
+        felt.extend(tick_items(&mut self.safe_unwrap_mut().items));
+        felt.extend(tick_items(&mut self.safe_unwrap_mut().clothing));
+
+        let mut effects = std::mem::take(&mut self.safe_unwrap_mut().effects);
+        for active in effects.iter_mut() {
+            active.remaining = active.remaining.saturating_sub(1);
+            match active.effect {
+                Effect::Poison { amount, .. } => {
+                    self.hurt(amount.max(0) as usize);
+                    felt.push("the poison burns in your veins".to_owned());
+                }
+                Effect::Buff { stat, amount, .. } if active.remaining == 0 => {
+                    if let Some(m) = self.meter_mut(stat) {
+                        let val = m.current() - amount;
+                        m.set(val);
+                    }
+                    felt.push(format!("your {} returns to normal", stat));
+                }
+                _ => (),
+            }
+        }
+        effects.retain(|e| e.remaining > 0);
+        self.safe_unwrap_mut().effects = effects;
+
+        for (stat, hungry, empty) in &[
+            (Stat::Hunger, "you are getting hungry", "you are starving"),
+            (Stat::Thirst, "you are getting thirsty", "you are parched"),
+        ] {
+            if let Some(m) = self.meter_mut(*stat) {
+                let old = m.current();
+                let val = (old - 1).max(0);
+                m.set(val);
+                match val {
+                    _ if val == old => (),
+                    PECKISH => felt.push((*hungry).to_owned()),
+                    0 => felt.push((*empty).to_owned()),
+                    _ => (),
+                }
+            }
+        }
+
+        felt
+    }
+
+    pub fn restore(&mut self) {
+        for meter in self.safe_unwrap_mut().stats.iter_mut() {
+            let max = meter.max();
+            meter.set(max);
+        }
+    }
+
     pub fn hp(&self) -> i64 {
         use meter::MeterKind::*;
         self.stats()
@@ -346,6 +552,190 @@ impl PlayerType {
             .current()
     }
 
+    pub fn strength(&self) -> i64 {
+        use meter::MeterKind::*;
+        self.stats()
+            .iter()
+            .find(|s| matches!(s, Strength(_)))
+            .map_or(DEFAULT_STRENGTH, |s| s.current())
+    }
+
+    pub fn dexterity(&self) -> i64 {
+        use meter::MeterKind::*;
+        self.stats()
+            .iter()
+            .find(|s| matches!(s, Dexterity(_)))
+            .map_or(DEFAULT_DEXTERITY, |s| s.current())
+    }
+
+    /// The total weight of everything the player is holding or wearing
+    pub fn carried_weight(&self) -> u32 {
+        let p = self.safe_unwrap();
+        p.items
+            .list()
+            .iter()
+            .chain(p.clothing.list().iter())
+            .map(|i| i.weight())
+            .sum()
+    }
+
+    pub fn carry_limit(&self) -> u32 {
+        self.strength().max(0) as u32 * CARRY_PER_STRENGTH
+    }
+
+    pub fn can_carry(&self, weight: u32) -> bool {
+        self.carried_weight() + weight <= self.carry_limit()
+    }
+
+    /// A player carrying more than half of what they can manage tires more quickly
+    pub fn is_encumbered(&self) -> bool {
+        self.carried_weight() * 2 > self.carry_limit()
+    }
+
+    /// How many movement points it takes to go from one room to the next
+    pub fn move_cost(&self) -> i64 {
+        if self.is_encumbered() {
+            ENCUMBERED_MOVE_COST
+        } else {
+            1
+        }
+    }
+
+    pub fn movement(&self) -> i64 {
+        use meter::MeterKind::*;
+        self.stats()
+            .iter()
+            .find(|s| matches!(s, Movement(_)))
+            .map_or(0, |s| s.current())
+    }
+
+    pub fn tire(&mut self, amt: i64) {
+        use meter::MeterKind::*;
+        if let Some(m) = self
+            .safe_unwrap_mut()
+            .stats
+            .iter_mut()
+            .find(|s| matches!(s, Movement(_)))
+        {
+            let current = m.current();
+            m.set((current - amt).max(0));
+        }
+    }
+
+    /// Restores the player's movement points, as after a good night's sleep
+    pub fn rest(&mut self) {
+        use meter::MeterKind::*;
+        if let Some(m) = self
+            .safe_unwrap_mut()
+            .stats
+            .iter_mut()
+            .find(|s| matches!(s, Movement(_)))
+        {
+            let max = m.max();
+            m.set(max);
+        }
+    }
+
+    pub fn gold(&self) -> u64 {
+        self.safe_unwrap().gold
+    }
+
+    pub fn earn(&mut self, amt: u64) {
+        let p = self.safe_unwrap_mut();
+        p.gold = p.gold.saturating_add(amt);
+    }
+
+    /// Takes `amt` gold from the player, if they have that much
+    pub fn pay(&mut self, amt: u64) -> bool {
+        let p = self.safe_unwrap_mut();
+        if p.gold < amt {
+            return false;
+        }
+        p.gold -= amt;
+        true
+    }
+
+    /// The shop this player keeps, if they're a shopkeeper
+    pub fn shop(&self) -> Option<&Shop> {
+        match self {
+            PlayerType::Npc(npc) => npc.shop(),
+            _ => None,
+        }
+    }
+
+    pub fn role(&self) -> Role {
+        self.safe_unwrap().role
+    }
+
+    pub fn set_role(&mut self, role: Role) {
+        self.safe_unwrap_mut().role = role;
+    }
+
+    /// Whether the player has chosen not to hear from the player with the given name
+    pub fn ignores(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.safe_unwrap().ignoring.iter().any(|n| *n == name)
+    }
+
+    pub fn is_subscribed(&self, channel: &str) -> bool {
+        self.safe_unwrap().channels.iter().any(|c| c == channel)
+    }
+
+    pub fn subscribe(&mut self, channel: &str) {
+        if !self.is_subscribed(channel) {
+            self.safe_unwrap_mut().channels.push(channel.to_owned());
+        }
+    }
+
+    /// Subscribes to the channel if the player isn't subscribed, or unsubscribes if they are.
+    /// Returns whether the player is now subscribed.
+    pub fn toggle_subscription(&mut self, channel: &str) -> bool {
+        let channels = &mut self.safe_unwrap_mut().channels;
+        toggle(channels, channel)
+    }
+
+    pub fn is_muted(&self, channel: &str) -> bool {
+        self.safe_unwrap().muted.iter().any(|c| c == channel)
+    }
+
+    /// Mutes or unmutes the channel. Returns whether the channel is now muted.
+    pub fn toggle_mute(&mut self, channel: &str) -> bool {
+        let muted = &mut self.safe_unwrap_mut().muted;
+        toggle(muted, channel)
+    }
+
+    pub fn ignoring(&self) -> &[String] {
+        &self.safe_unwrap().ignoring
+    }
+
+    /// Ignores the named player, or stops ignoring them if they are already ignored. Returns
+    /// whether the player is now ignored.
+    pub fn toggle_ignore(&mut self, name: &str) -> bool {
+        let ignoring = &mut self.safe_unwrap_mut().ignoring;
+        toggle(ignoring, &name.to_lowercase())
+    }
+
+    /// Keeps the player off public channels until the given time. `None` lifts the silence.
+    pub fn silence(&mut self, until: Option<Instant>) {
+        self.safe_unwrap_mut().silenced_until = until;
+    }
+
+    pub fn is_silenced(&self) -> bool {
+        match self.safe_unwrap().silenced_until {
+            Some(t) => Instant::now() < t,
+            None => false,
+        }
+    }
+
+    /// The last player to send this player a tell
+    pub fn reply_to(&self) -> Option<u128> {
+        self.safe_unwrap().reply_to
+    }
+
+    pub fn set_reply_to(&mut self, id: u128) {
+        self.safe_unwrap_mut().reply_to = Some(id);
+    }
+
     pub fn set_loc(&mut self, new_loc: Coord) {
         self.safe_unwrap_mut().loc = new_loc;
     }
@@ -358,6 +748,19 @@ impl PlayerType {
         &mut self.safe_unwrap_mut().clothing
     }
 
+    /// The weapon the player is fighting with, if they're holding one
+    pub fn wielded(&self) -> Option<&WeaponStats> {
+        self.worn(Slot::Held).and_then(|i| i.weapon())
+    }
+
+    /// What the player is wearing in the given slot
+    pub fn worn(&self, slot: Slot) -> Option<&Item> {
+        self.clothing()
+            .list()
+            .into_iter()
+            .find(|i| i.slot() == Some(slot))
+    }
+
     pub fn souls(&self) -> &List<SoulKind, Quality> {
         &self.safe_unwrap().souls
     }
@@ -413,6 +816,19 @@ impl PlayerType {
     }
 }
 
+fn toggle(list: &mut Vec<String>, s: &str) -> bool {
+    match list.iter().position(|x| x == s) {
+        Some(pos) => {
+            list.remove(pos);
+            false
+        }
+        None => {
+            list.push(s.to_owned());
+            true
+        }
+    }
+}
+
 pub enum ConnectionStatus {
     Npc,
     Connected,
@@ -1,7 +1,12 @@
-use super::{Player, PlayerType};
+use super::shop::Shop;
+use super::{Player, PlayerType, CORPSE_DECAY};
 use crate::attribute::Quality;
-use crate::describe::Description;
-use crate::item::Item;
+use crate::describe::{Describe, Description};
+use crate::error::EnnuiError;
+use crate::item::list::Receptacle;
+use crate::item::template::Templates;
+use crate::item::timer::Timer;
+use crate::item::{DescriptionWithQualities, Item, YamlItem, YamlItemList};
 use crate::list::ListTrait;
 use crate::location::Coord;
 use crate::soul::SoulKind;
@@ -29,6 +34,21 @@ pub struct YamlPlayer {
     pub soul: SoulKind,
     #[serde(default)]
     pub loc: Coord,
+    #[serde(default)]
+    pub items: Vec<YamlItem>,
+    #[serde(default)]
+    pub shop: Option<Shop>,
+}
+
+impl YamlPlayer {
+    /// Swaps any item references the NPC carries or sells for copies of their templates
+    pub fn resolve(&mut self, templates: &Templates) -> Result<(), EnnuiError> {
+        templates.resolve_all(&mut self.items)?;
+        if let Some(shop) = &mut self.shop {
+            templates.resolve_all(&mut shop.stock)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -36,6 +56,7 @@ pub struct Npc {
     player: Player,
     ai_type: Option<AI>,
     soul: SoulKind,
+    shop: Option<Shop>,
     tx: Option<Mutex<Sender<NpcMessage>>>,
 }
 
@@ -81,7 +102,25 @@ impl From<Player> for Item {
             items.insert_item(item);
         }
 
-        Item::Container(Box::new(items))
+        let rots = format!("the corpse of {} rots away", name);
+        let info = DescriptionWithQualities {
+            info: Description {
+                name: format!("corpse of {}", name),
+                display,
+                description,
+                handle,
+            },
+            attr: attributes,
+            timer: Some(Timer::new(CORPSE_DECAY, Some(rots))),
+            ..DescriptionWithQualities::default()
+        };
+
+        Item::Container(Box::new(Receptacle {
+            items,
+            info,
+            lid: None,
+            capacity: None,
+        }))
     }
 }
 
@@ -92,18 +131,27 @@ impl From<YamlPlayer> for Npc {
             ai_type,
             loc,
             soul,
+            items,
+            shop,
         } = other;
 
         let ai_type = ai_type;
         let mut p = Player::new();
         p.loc = loc;
         p.info = info;
+        p.items = YamlItemList::from(items).into();
+        if let Some(shop) = &shop {
+            for item in shop.fresh_stock() {
+                p.items.insert_item(item).ok();
+            }
+        }
 
         Self {
             player: p,
             ai_type,
             tx: None,
             soul,
+            shop,
         }
     }
 }
@@ -120,6 +168,7 @@ impl Npc {
             ai_type,
             tx: None,
             soul,
+            shop: None,
         }
     }
 
@@ -199,6 +248,28 @@ impl Npc {
     pub fn player_mut(&mut self) -> &mut Player {
         &mut self.player
     }
+
+    pub fn shop(&self) -> Option<&Shop> {
+        self.shop.as_ref()
+    }
+
+    /// Puts back whatever the shopkeeper has sold, once the shop is due a restock
+    pub fn restock(&mut self) {
+        let shop = match self.shop.as_mut() {
+            Some(shop) => shop,
+            None => return,
+        };
+        if !shop.tick() {
+            return;
+        }
+
+        for item in shop.fresh_stock() {
+            let items = &mut self.player.items;
+            if !items.list().iter().any(|i| i.name() == item.name()) {
+                items.insert_item(item).ok();
+            }
+        }
+    }
 }
 
 #[cfg(test)]
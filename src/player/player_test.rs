@@ -15,4 +15,123 @@ mod player_test {
         let y = Hit(x);
         assert_eq!(format!("{}", y), "HIT: [100 / 100]");
     }
+
+    #[test]
+    fn test_encumbrance() {
+        use crate::describe::Description;
+        use crate::item::{DescriptionWithQualities, Item};
+        use crate::list::ListTrait;
+        use crate::player::{Player, PlayerType};
+
+        let mut p = PlayerType::Human(Player::new());
+        assert_eq!(p.carry_limit(), 100);
+        assert_eq!(p.move_cost(), 1);
+
+        let anvil = DescriptionWithQualities {
+            info: Description::default(),
+            attr: vec![],
+            weight: 60,
+            slot: None,
+            weapon: None,
+            food: None,
+            price: 0,
+            timer: None,
+            count: 1,
+        };
+        p.insert_item(Item::Holdable(Box::new(anvil))).unwrap();
+        assert!(p.is_encumbered());
+        assert!(!p.can_carry(50));
+
+        let cost = p.move_cost();
+        p.tire(cost);
+        assert_eq!(p.movement(), 100 - cost);
+        p.rest();
+        assert_eq!(p.movement(), 100);
+    }
+
+    #[test]
+    fn test_food_effects() {
+        use crate::player::effect::{Effect, Food, Stat};
+        use crate::player::{Player, PlayerType};
+
+        let mut p = PlayerType::Human(Player::new());
+        let food = Food {
+            drink: false,
+            fills: 0,
+            effects: vec![
+                Effect::Buff {
+                    stat: Stat::Strength,
+                    amount: 4,
+                    ticks: 2,
+                },
+                Effect::Poison {
+                    amount: 5,
+                    ticks: 1,
+                },
+            ],
+        };
+
+        let hp = p.hp();
+        let felt = p.consume(&food);
+        assert_eq!(felt.len(), 2);
+        assert_eq!(p.strength(), 14);
+
+        let felt = p.tick();
+        assert!(felt.iter().any(|m| m.contains("poison")));
+        assert_eq!(p.hp(), hp - 5);
+        assert_eq!(p.strength(), 14);
+
+        let felt = p.tick();
+        assert!(felt
+            .iter()
+            .any(|m| m.contains("strength returns to normal")));
+        assert_eq!(p.strength(), 10);
+        assert_eq!(p.hp(), hp - 5);
+    }
+
+    #[test]
+    fn test_hunger() {
+        use crate::player::{Player, PlayerType};
+
+        let mut p = PlayerType::Human(Player::new());
+        assert!(p.tick().is_empty());
+
+        p.enable_hunger();
+        let felt: Vec<String> = (0..80).flat_map(|_| p.tick()).collect();
+        assert_eq!(
+            felt,
+            vec!["you are getting hungry", "you are getting thirsty"]
+        );
+    }
+
+    #[test]
+    fn test_worn_items_wear_away() {
+        use crate::describe::Description;
+        use crate::item::timer::Timer;
+        use crate::item::{DescriptionWithQualities, Item};
+        use crate::list::ListTrait;
+        use crate::player::{Player, PlayerType};
+
+        let mut p = PlayerType::Human(Player::new());
+        let garland = DescriptionWithQualities {
+            info: Description {
+                name: "garland of flowers".to_owned(),
+                ..Description::default()
+            },
+            attr: vec![],
+            weight: 0,
+            slot: None,
+            weapon: None,
+            food: None,
+            price: 0,
+            timer: Some(Timer::new(1, Some("your garland wilts".to_owned()))),
+            count: 1,
+        };
+        p.clothing_mut()
+            .insert_item(Item::Clothing(Box::new(garland)))
+            .unwrap();
+
+        assert_eq!(p.tick(), vec!["your garland wilts"]);
+        assert!(p.clothing().list().is_empty());
+    }
 }
@@ -0,0 +1,93 @@
+use crate::attribute::Quality;
+use crate::item::{Item, YamlItem, YamlItemList};
+use crate::list::List;
+use serde::{Deserialize, Serialize};
+
+/// `Shop` turns an NPC into a shopkeeper. `stock` is what the shopkeeper keeps on the shelves,
+/// and whatever has been sold is put back every `restock` ticks. Prices are the item's `price`,
+/// scaled by `buy_markup` when a player buys and `sell_markup` when a player sells, both as
+/// percentages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shop {
+    #[serde(default)]
+    pub stock: Vec<YamlItem>,
+    #[serde(default = "default_buy_markup")]
+    pub buy_markup: u64,
+    #[serde(default = "default_sell_markup")]
+    pub sell_markup: u64,
+    #[serde(default = "default_restock")]
+    pub restock: u32,
+    #[serde(skip)]
+    since_restock: u32,
+}
+
+fn default_buy_markup() -> u64 {
+    120
+}
+
+fn default_sell_markup() -> u64 {
+    50
+}
+
+fn default_restock() -> u32 {
+    10
+}
+
+impl Shop {
+    /// What a player pays for an item worth `price`, or `None` if it's too much to count. Nothing
+    /// is ever given away.
+    pub fn buy_price(&self, price: u64) -> Option<u64> {
+        Some((price.checked_mul(self.buy_markup)? / 100).max(1))
+    }
+
+    /// What the shopkeeper pays a player for an item worth `price`, or `None` if it's too much to
+    /// count
+    pub fn sell_price(&self, price: u64) -> Option<u64> {
+        Some(price.checked_mul(self.sell_markup)? / 100)
+    }
+
+    /// Counts down to the next restock, returning whether it's time
+    pub fn tick(&mut self) -> bool {
+        self.since_restock += 1;
+        if self.since_restock >= self.restock {
+            self.since_restock = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A fresh copy of everything the shop stocks
+    pub fn fresh_stock(&self) -> List<Item, Quality> {
+        YamlItemList::from(self.stock.clone()).into()
+    }
+}
+
+#[cfg(test)]
+mod shop_test {
+    use super::*;
+
+    #[test]
+    fn test_shop_yaml() {
+        let x = r#"---
+buy_markup: 150
+stock:
+- Edible:
+    name: bread
+    display: A loaf of bread sits here
+    description: It's a loaf of bread.
+    handle: [bread, loaf]
+    price: 4
+"#;
+        let mut shop: Shop = serde_yaml::from_str(x).unwrap();
+        assert_eq!(shop.buy_price(4), Some(6));
+        assert_eq!(shop.sell_price(4), Some(2));
+        assert_eq!(shop.buy_price(0), Some(1));
+        assert_eq!(shop.buy_price(u64::MAX), None);
+        assert_eq!(shop.sell_price(u64::MAX), None);
+        assert_eq!(shop.fresh_stock().into_inner().len(), 1);
+
+        assert!((1..shop.restock).all(|_| !shop.tick()));
+        assert!(shop.tick());
+    }
+}
@@ -4,7 +4,7 @@ use crate::attribute::Quality;
 use crate::describe::Describe;
 use crate::handle;
 use crate::hook::Hook;
-use crate::list::{List, ListTrait};
+use crate::list::{List, ListTrait, Stack};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::error::Error;
@@ -74,6 +74,8 @@ impl Describe for SoulKind {
     }
 }
 
+impl Stack for SoulKind {}
+
 impl List<SoulKind, Quality> {
     pub fn process_recipe(&mut self, recipe: &recipe::Recipe) -> bool {
         let list = self.list();
@@ -1,5 +1,8 @@
 use crate::fight::FightMessage;
-use crate::game::Game;
+use crate::game::{Game, NpcInit};
+use crate::item::Item;
+use crate::location::Coord;
+use crate::player::PlayerType;
 use crate::text::message::{Broadcast, FightAudience, MessageFormat};
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
@@ -27,6 +30,9 @@ impl MessageHandler for MessageReceiver {
 
 pub enum DiscreteMessage {
     KillPlayer(u128),
+    SpawnNpc(Box<PlayerType>),
+    PlaceItem(Coord, Box<Item>),
+    Tick,
 }
 
 pub struct GameActor(pub Receiver<DiscreteMessage>);
@@ -43,6 +49,24 @@ impl MessageHandler for GameActor {
                             eprintln!("in file {} on line number {}", file!(), line!());
                         }
                     }
+                    DiscreteMessage::SpawnNpc(npc) => {
+                        if let Err(e) = caster.init_npcs(vec![*npc]) {
+                            eprintln!("[{}]: {:?}", "ERROR".color(super::Color::Magenta), e);
+                            eprintln!("in file {} on line number {}", file!(), line!());
+                        }
+                    }
+                    DiscreteMessage::PlaceItem(loc, item) => {
+                        if let Err(e) = caster.lock().unwrap().place_item(loc, *item) {
+                            eprintln!("[{}]: {:?}", "ERROR".color(super::Color::Magenta), e);
+                            eprintln!("in file {} on line number {}", file!(), line!());
+                        }
+                    }
+                    DiscreteMessage::Tick => {
+                        if let Err(e) = caster.lock().unwrap().tick() {
+                            eprintln!("[{}]: {:?}", "ERROR".color(super::Color::Magenta), e);
+                            eprintln!("in file {} on line number {}", file!(), line!());
+                        }
+                    }
                 }
             }
         })
@@ -0,0 +1,64 @@
+use crate::player::Role;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const DEFAULT_HISTORY: usize = 20;
+
+/// `ChatChannel` is a named, game-wide channel, like `gossip` or `newbie`. Only players whose
+/// role is at least `role` may hear or use it, and the last few messages are kept so that players
+/// who join later can catch up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatChannel {
+    pub name: String,
+    #[serde(default)]
+    pub role: Role,
+    /// Whether new players are subscribed to the channel
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default = "default_history")]
+    pub history_len: usize,
+    #[serde(skip_serializing, skip_deserializing)]
+    history: VecDeque<String>,
+}
+
+fn default_history() -> usize {
+    DEFAULT_HISTORY
+}
+
+impl ChatChannel {
+    pub fn record(&mut self, line: String) {
+        self.history.push_back(line);
+        while self.history.len() > self.history_len {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &String> {
+        self.history.iter()
+    }
+}
+
+#[cfg(test)]
+mod chat_test {
+    use super::*;
+
+    #[test]
+    fn test_channel_history_is_bounded() {
+        let x = r#"---
+name: builder
+role: Builder
+history_len: 2
+"#;
+        let mut c: ChatChannel = serde_yaml::from_str(x).unwrap();
+        assert_eq!(c.role, Role::Builder);
+        assert!(!c.default);
+
+        c.record("one".into());
+        c.record("two".into());
+        c.record("three".into());
+        assert_eq!(
+            c.history().cloned().collect::<Vec<_>>(),
+            vec!["two", "three"]
+        );
+    }
+}
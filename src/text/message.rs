@@ -192,7 +192,7 @@ mod test_message {
         let mut g = Game::new().unwrap();
         let s = "poo butts poo".to_owned();
         let n = 8_u128;
-        let room = g.get_room(Coord(0, 0)).unwrap();
+        let room = g.get_room(Coord(0, 0, 0)).unwrap();
         let _audience = Audience(n, room.players().except(n));
         g.send(&n, &s);
     }
@@ -1,5 +1,7 @@
 pub mod channel;
+pub mod chat;
 pub mod message;
+pub mod social;
 
 pub enum Color {
     Red,
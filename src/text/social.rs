@@ -0,0 +1,86 @@
+use crate::fight::FightMessage;
+use serde::{Deserialize, Serialize};
+
+/// `SocialMessage` holds the text of one variant of a social. `{actor}` and `{target}` are
+/// replaced with the names of the players involved.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SocialMessage {
+    #[serde(rename = "self")]
+    pub to_self: String,
+    #[serde(default)]
+    pub object: Option<String>,
+    #[serde(default)]
+    pub others: Option<String>,
+}
+
+/// `Social` is a canned emote, like `smile` or `bow`. Each social may be used on its own, on
+/// another player in the room, or on oneself.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Social {
+    pub name: String,
+    pub untargeted: SocialMessage,
+    #[serde(default)]
+    pub targeted: Option<SocialMessage>,
+    #[serde(default)]
+    pub reflexive: Option<SocialMessage>,
+}
+
+impl SocialMessage {
+    fn render(&self, actor: &str, target: &str) -> FightMessage {
+        let fill = |s: &str| s.replace("{actor}", actor).replace("{target}", target);
+
+        FightMessage {
+            s: fill(&self.to_self).into(),
+            obj: self.object.as_ref().map(|s| fill(s).into()),
+            oth: self.others.as_ref().map(|s| fill(s).into()),
+        }
+    }
+}
+
+impl Social {
+    pub fn untargeted(&self, actor: &str) -> FightMessage {
+        self.untargeted.render(actor, "")
+    }
+
+    pub fn targeted(&self, actor: &str, target: &str) -> Option<FightMessage> {
+        Some(self.targeted.as_ref()?.render(actor, target))
+    }
+
+    pub fn reflexive(&self, actor: &str) -> Option<FightMessage> {
+        Some(self.reflexive.as_ref()?.render(actor, actor))
+    }
+}
+
+#[cfg(test)]
+mod social_test {
+    use super::*;
+    use crate::text::message::Message;
+
+    #[test]
+    fn social_import_test() {
+        let x = r#"---
+name: bow
+untargeted:
+  self: you bow deeply
+  others: "{actor} bows deeply"
+targeted:
+  self: "you bow before {target}"
+  object: "{actor} bows before you"
+  others: "{actor} bows before {target}"
+"#;
+        let s: Social = serde_yaml::from_str(x).unwrap();
+        assert_eq!(s.name, "bow");
+
+        let m = s.untargeted("Bill");
+        assert_eq!(m.to_self(), "you bow deeply");
+        assert_eq!(m.to_object(), None);
+        assert_eq!(m.to_others().unwrap(), "Bill bows deeply");
+
+        let m = s.targeted("Bill", "Mitchell").unwrap();
+        assert_eq!(m.to_self(), "you bow before Mitchell");
+        assert_eq!(m.to_object().unwrap(), "Bill bows before you");
+        assert_eq!(m.to_others().unwrap(), "Bill bows before Mitchell");
+
+        assert!(s.reflexive("Bill").is_none());
+    }
+}